@@ -6,6 +6,7 @@ export const deleteTweet = async tweet => {
     accounts: {
       author: wallet.value.publicKey,
       tweet: tweet.publicKey,
+      postedBy: tweet.postedBy,
     },
   });
 };
@@ -21,6 +21,7 @@ export const topicFilter = topic => ({
       8 + // Discriminator.
       32 + // Author public key.
       8 + // Timestamp.
+      1 + // Reply to option (none for top-level tweets).
       4, // Topic string prefix.
     bytes: bs58.encode(Buffer.from(topic)),
   },
@@ -1,7 +1,77 @@
-import { web3 } from "@project-serum/anchor";
+import { BN, utils, web3 } from "@project-serum/anchor";
 import { useWorkspace } from "@/composables";
 import { Tweet } from "@/models";
 
+// Mirrors the on-chain hashtag extraction: distinct, lowercase, 32 bytes max.
+const extractHashtags = content => {
+  const hashtags = [];
+  for (const word of content.split(/\s+/)) {
+    if (!word.startsWith("#")) continue;
+    const [tag] = word
+      .slice(1)
+      .toLowerCase()
+      .match(/^[\p{L}\p{N}_]*/u);
+    if (tag && Buffer.from(tag).length <= 32 && !hashtags.includes(tag)) {
+      hashtags.push(tag);
+    }
+  }
+  return hashtags;
+};
+
+const hashtagAccounts = async (programId, content) => {
+  return await Promise.all(
+    extractHashtags(content).map(async tag => {
+      const [pubkey] = await web3.PublicKey.findProgramAddress(
+        [Buffer.from("hashtag"), Buffer.from(tag)],
+        programId
+      );
+      return { pubkey, isWritable: true, isSigner: false };
+    })
+  );
+};
+
+export const getConfigAddress = async programId => {
+  const [config] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("config")],
+    programId
+  );
+  return config;
+};
+
+export const getTreasuryAddress = async programId => {
+  const [treasury] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("treasury")],
+    programId
+  );
+  return treasury;
+};
+
+export const topicIndexAccount = async (programId, topic) => {
+  const [pubkey] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("topic"), Buffer.from(utils.sha256.hash(topic), "hex")],
+    programId
+  );
+  return { pubkey, isWritable: true, isSigner: false };
+};
+
+// The author's profile and its current index page, both required to post.
+export const profileAccounts = async (program, author) => {
+  const [profile] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("profile"), author.toBuffer()],
+    program.programId
+  );
+  const profileAccount = await program.account.userProfile.fetchNullable(profile);
+  const page = profileAccount ? profileAccount.indexPage : 0;
+  const [index] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("index"), author.toBuffer(), new BN(page).toArrayLike(Buffer, "le", 4)],
+    program.programId
+  );
+  return [
+    { pubkey: profile, isWritable: true, isSigner: false },
+    { pubkey: index, isWritable: true, isSigner: false },
+  ];
+};
+
 // 1. Define the sendTweet endpoint.
 export const sendTweet = async (topic, content) => {
   const { wallet, program } = useWorkspace();
@@ -10,12 +80,19 @@ export const sendTweet = async (topic, content) => {
   const tweet = web3.Keypair.generate();
 
   // 3. Send a "SendTweet" instruction with the right data and the right accounts.
-  await program.value.rpc.sendTweet(topic, content, {
+  await program.value.rpc.sendTweet(topic, content, [], null, 0, null, [], null, null, null, [], {
     accounts: {
       author: wallet.value.publicKey,
       tweet: tweet.publicKey,
+      config: await getConfigAddress(program.value.programId),
+      treasury: await getTreasuryAddress(program.value.programId),
       systemProgram: web3.SystemProgram.programId,
     },
+    remainingAccounts: [
+      ...(await profileAccounts(program.value, wallet.value.publicKey)),
+      ...(topic ? [await topicIndexAccount(program.value.programId, topic)] : []),
+      ...(await hashtagAccounts(program.value.programId, content)),
+    ],
     signers: [tweet],
   });
 
@@ -1,13 +1,61 @@
+import { BN, web3 } from "@project-serum/anchor";
 import { useWorkspace } from "@/composables";
+import { getConfigAddress, topicIndexAccount } from "./send-tweet";
+
+const getNextRevisionAddress = async (program, tweet) => {
+  const { revisionCount } = await program.account.tweet.fetch(tweet);
+  const [revision] = await web3.PublicKey.findProgramAddress(
+    [
+      Buffer.from("revision"),
+      tweet.toBuffer(),
+      new BN(revisionCount).toArrayLike(Buffer, "le", 2),
+    ],
+    program.programId
+  );
+  return revision;
+};
+
+const getEditLogAddress = async (program, tweet) => {
+  const [editLog] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("editlog"), tweet.toBuffer()],
+    program.programId
+  );
+  return editLog;
+};
+
+// Moves the tweet from the index of its old topic to the index of its new one.
+const topicChangeAccounts = async (programId, oldTopic, newTopic) => {
+  if (oldTopic === newTopic) return [];
+  const topics = [oldTopic, newTopic].filter(topic => topic);
+  return await Promise.all(
+    topics.map(topic => topicIndexAccount(programId, topic))
+  );
+};
 
 export const updateTweet = async (tweet, topic, content) => {
   const { wallet, program } = useWorkspace();
-  await program.value.rpc.updateTweet(topic, content, {
-    accounts: {
-      author: wallet.value.publicKey,
-      tweet: tweet.publicKey,
-    },
-  });
+  await program.value.rpc.updateTweet(
+    topic,
+    content,
+    tweet.replyPermission,
+    tweet.media,
+    {
+      accounts: {
+        author: wallet.value.publicKey,
+        tweet: tweet.publicKey,
+        postedBy: tweet.postedBy,
+        revision: await getNextRevisionAddress(program.value, tweet.publicKey),
+        editLog: await getEditLogAddress(program.value, tweet.publicKey),
+        config: await getConfigAddress(program.value.programId),
+        systemProgram: web3.SystemProgram.programId,
+      },
+      remainingAccounts: await topicChangeAccounts(
+        program.value.programId,
+        tweet.topic,
+        topic
+      ),
+    }
+  );
 
   tweet.topic = topic;
   tweet.content = content;
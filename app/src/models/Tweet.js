@@ -4,20 +4,48 @@ export class Tweet {
   constructor(publicKey, accountData) {
     this.publicKey = publicKey;
     this.author = accountData.author;
+    this.postedBy = accountData.postedBy;
     this.timestamp = accountData.timestamp.toString();
     this.topic = accountData.topic;
     this.content = accountData.content;
+    this.likeCount = accountData.likeCount.toNumber();
+    this.replyCount = accountData.replyCount.toNumber();
+    this.retweetCount = accountData.retweetCount.toNumber();
+    this.quoteCount = accountData.quoteCount.toNumber();
+    this.replyPermission = accountData.replyPermission;
+    this.media = accountData.media;
+    this.preview = accountData.preview;
+    // Ref tweets store their content at `contentUri` rather than in `content`.
+    this.storageKind = accountData.storageKind;
+    this.contentUri = accountData.contentUri;
+    this.reactionCounts = accountData.reactionCounts;
+    this.views = accountData.views.toNumber();
+    this.viewCount = accountData.viewCount.toNumber();
+    this.space = accountData.space;
+    this.coAuthors = accountData.coAuthors;
   }
 
   get key() {
     return this.publicKey.toBase58();
   }
 
+  // Long-form tweets are shown by their preview, derived on chain from the content.
+  get summary() {
+    return this.preview || this.content;
+  }
+
   get author_display() {
     const author = this.author.toBase58();
     return author.slice(0, 4) + ".." + author.slice(-4);
   }
 
+  // Every interaction weighs the same, clients can rank their feed with it.
+  get engagement_score() {
+    return (
+      this.likeCount + this.replyCount + this.retweetCount + this.quoteCount
+    );
+  }
+
   get created_at() {
     return dayjs.unix(this.timestamp).format("lll");
   }
@@ -0,0 +1,96 @@
+//! Seeds, account sizes and field offsets that clients need to find accounts
+//! with `getProgramAccounts` without re-deriving them from the structs.
+//!
+//! Every account starts with its 8 byte discriminator, followed by the fields
+//! in declaration order. Every account kind has its own size, accounts that
+//! would share one are padded, so a `dataSize` filter alone returns a single
+//! kind. A `memcmp` on the discriminator at offset 0 works as well.
+
+use crate::*;
+
+// PDA seed prefixes.
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const STATS_SEED: &[u8] = b"stats";
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+pub const TWEET_SEED: &[u8] = b"tweet";
+pub const PROFILE_SEED: &[u8] = b"profile";
+pub const HANDLE_SEED: &[u8] = b"handle";
+pub const AUTHOR_INDEX_SEED: &[u8] = b"index";
+pub const RECENT_POST_SEED: &[u8] = b"recent";
+pub const DELEGATE_SEED: &[u8] = b"delegate";
+pub const LIKE_SEED: &[u8] = b"like";
+pub const LIKE_CLAIM_SEED: &[u8] = b"like_claim";
+pub const REACTION_SEED: &[u8] = b"reaction";
+pub const FOLLOW_SEED: &[u8] = b"follow";
+pub const BLOCK_SEED: &[u8] = b"block";
+pub const MUTE_SEED: &[u8] = b"mute";
+pub const RETWEET_SEED: &[u8] = b"retweet";
+pub const BOOKMARK_SEED: &[u8] = b"bookmark";
+pub const VIEW_SEED: &[u8] = b"view";
+pub const TIP_STATS_SEED: &[u8] = b"tip_stats";
+pub const SUBSCRIPTION_SEED: &[u8] = b"sub";
+pub const REVISION_SEED: &[u8] = b"revision";
+pub const EDIT_LOG_SEED: &[u8] = b"editlog";
+pub const REPORT_SEED: &[u8] = b"report";
+pub const TOPIC_INDEX_SEED: &[u8] = b"topic";
+pub const HASHTAG_SEED: &[u8] = b"hashtag";
+pub const MENTION_SEED: &[u8] = b"mention";
+pub const POLL_SEED: &[u8] = b"poll";
+pub const POLL_VOTE_SEED: &[u8] = b"poll_vote";
+pub const CONVERSATION_SEED: &[u8] = b"conversation";
+pub const LIST_SEED: &[u8] = b"list";
+pub const LIST_MEMBER_SEED: &[u8] = b"list_member";
+pub const LIST_ITEM_SEED: &[u8] = b"listitem";
+pub const SPACE_SEED: &[u8] = b"space";
+pub const SPACE_MEMBER_SEED: &[u8] = b"space_member";
+// Base account sizes. Tweets grow past `TWEET_SPACE` when they carry media or
+// long content, so `dataSize` only matches plain tweets. Ref tweets drop the
+// inline content and take between 1178 and 1306 bytes. No other kind is as
+// large as a tweet or falls in the range of ref tweets, so a `dataSize` filter
+// for another kind never matches a tweet.
+pub const TWEET_SPACE: usize = Tweet::LEN;
+pub const PROFILE_SPACE: usize = UserProfile::LEN;
+pub const COMMENT_SPACE: usize = Comment::LEN;
+pub const LIKE_SPACE: usize = Like::LEN;
+pub const LIKE_CLAIM_SPACE: usize = LikeClaim::LEN;
+pub const FOLLOW_SPACE: usize = Follow::LEN;
+pub const RETWEET_SPACE: usize = Retweet::LEN;
+pub const BOOKMARK_SPACE: usize = Bookmark::LEN;
+pub const VIEW_SPACE: usize = View::LEN;
+pub const REACTION_SPACE: usize = Reaction::LEN;
+pub const BLOCK_SPACE: usize = Block::LEN;
+pub const MUTE_SPACE: usize = Mute::LEN;
+pub const HANDLE_SPACE: usize = Handle::LEN;
+pub const SUBSCRIPTION_SPACE: usize = Subscription::LEN;
+pub const SPACE_ACCOUNT_SPACE: usize = Space::LEN;
+pub const SPACE_MEMBER_SPACE: usize = SpaceMember::LEN;
+pub const DELEGATE_SPACE: usize = Delegate::LEN;
+pub const RECENT_POST_SPACE: usize = RecentPost::LEN;
+pub const AUTHOR_INDEX_SPACE: usize = AuthorIndex::LEN;
+pub const TOPIC_INDEX_SPACE: usize = TopicIndex::LEN;
+pub const HASHTAG_SPACE: usize = Hashtag::LEN;
+pub const MENTION_SPACE: usize = Mention::LEN;
+pub const TIP_STATS_SPACE: usize = TipStats::LEN;
+pub const POLL_SPACE: usize = Poll::LEN;
+pub const POLL_VOTE_SPACE: usize = PollVote::LEN;
+pub const LIST_SPACE: usize = List::LEN;
+pub const LIST_MEMBER_SPACE: usize = ListMember::LEN;
+pub const TWEET_LIST_SPACE: usize = TweetList::LEN;
+pub const LIST_ITEM_SPACE: usize = ListItem::LEN;
+pub const REPORT_SPACE: usize = Report::LEN;
+pub const REVISION_SPACE: usize = TweetRevision::LEN;
+pub const EDIT_LOG_SPACE: usize = EditLog::LEN;
+pub const CONVERSATION_SPACE: usize = Conversation::LEN;
+pub const MESSAGE_SPACE: usize = Message::LEN;
+pub const CONFIG_SPACE: usize = Config::LEN;
+pub const TREASURY_SPACE: usize = Treasury::LEN;
+pub const GLOBAL_STATS_SPACE: usize = GlobalStats::LEN;
+pub const DAILY_STATS_SPACE: usize = DailyStats::LEN;
+
+// Fields that sit at the same offset in every tweet, right after the
+// discriminator, so clients can `memcmp` on them.
+pub const TWEET_AUTHOR_OFFSET: usize = DISCRIMINATOR_LENGTH;
+pub const TWEET_TIMESTAMP_OFFSET: usize = TWEET_AUTHOR_OFFSET + PUBLIC_KEY_LENGTH;
+// Short alias of `TWEET_AUTHOR_OFFSET` for author filters.
+pub const AUTHOR_OFFSET: usize = TWEET_AUTHOR_OFFSET;
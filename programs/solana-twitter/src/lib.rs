@@ -1,5 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::{ed25519_program, system_instruction, system_program, sysvar};
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use constants::*;
+use mpl_token_metadata::instruction::{create_master_edition_v3, create_metadata_accounts_v2};
+use std::iter::Peekable;
+
+pub mod constants;
 
 declare_id!("H4FBVtcR7yKNWJWnwK6wwEtREYaF5Vi6w9R1uHZXRw7F");
 
@@ -8,101 +21,7258 @@ pub mod solana_twitter {
 
     use super::*;
 
-    pub fn send_tweet(ctx: Context<SendTweet>, topic: String, content: String) -> Result<()> {
+    pub fn send_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendTweet<'info>>,
+        topic: String,
+        content: String,
+        mentions: Vec<Pubkey>,
+        expires_at: Option<i64>,
+        reply_permission: u8,
+        scheduled_reveal: Option<i64>,
+        media: Vec<MediaAttachment>,
+        gate: Option<TokenGate>,
+        on_behalf_of: Option<Pubkey>,
+        space: Option<Pubkey>,
+        co_authors: Vec<CoAuthor>,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let author: &Signer = &ctx.accounts.author;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+
+        // Limits apply to what gets stored.
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, config)?;
+
+        if let Some(expires_at) = expires_at {
+            if expires_at <= clock.unix_timestamp {
+                return Err(error!(ErrorCode::InvalidExpiration));
+            }
+        }
+
+        if let Some(scheduled_reveal) = scheduled_reveal {
+            if scheduled_reveal <= clock.unix_timestamp {
+                return Err(error!(ErrorCode::InvalidRevealTime));
+            }
+        }
+
+        if reply_permission > REPLY_NOBODY {
+            return Err(error!(ErrorCode::InvalidReplyPermission));
+        }
+
+        validate_media(&media)?;
+
+        if mentions.len() > MAX_MENTIONS {
+            return Err(error!(ErrorCode::TooManyMentions));
+        }
+
+        if mentions
+            .iter()
+            .enumerate()
+            .any(|(i, mention)| mentions[..i].contains(mention))
+        {
+            return Err(error!(ErrorCode::DuplicateMention));
+        }
+
+        charge_post_fee(
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        // Remaining accounts, in order:
+        // 1. The Delegate PDA of the owner and the signer, when posting on behalf of someone.
+        // 2. The Space and the author's SpaceMember PDA, when posting into a space.
+        // 3. The author's profile, required and created when they have none yet.
+        // 4. Its current AuthorIndex page, required.
+        // 5. The author's RecentPost, optional unless a cooldown is active.
+        // 6. The TopicIndex of the topic, optional.
+        // 7. One Hashtag PDA per distinct hashtag.
+        // 8. One Mention PDA per mentioned user.
+        // 9. The GlobalStats and today's DailyStats, both optional.
+        let mut remaining_accounts = ctx.remaining_accounts.iter().peekable();
+
+        let author_key = match on_behalf_of {
+            Some(owner) => {
+                ensure_delegate(&owner, author.key, &mut remaining_accounts, ctx.program_id)?;
+                owner
+            }
+            None => *author.key,
+        };
+        validate_co_authors(&co_authors, &author_key)?;
+
+        if let Some(space) = space {
+            post_to_space(space, &author_key, &mut remaining_accounts, ctx.program_id)?;
+        }
+
+        **tweet = Tweet {
+            mentions,
+            expires_at,
+            reply_permission,
+            // Time-locked tweets hold content made opaque by the client until their reveal.
+            scheduled_reveal,
+            is_revealed: scheduled_reveal.is_none(),
+            media,
+            gate_mint: gate.as_ref().map(|gate| gate.mint),
+            gate_min_amount: gate.map_or(0, |gate| gate.min_amount),
+            space,
+            co_authors,
+            ..Tweet::new(
+                author_key,
+                *author.key,
+                topic,
+                content,
+                clock.unix_timestamp,
+            )
+        };
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        let (profile_key, profile_bump) =
+            Pubkey::find_program_address(&[PROFILE_SEED, author_key.as_ref()], ctx.program_id);
+        // The profile is required so a deactivated author cannot post by leaving it out.
+        let profile_info = remaining_accounts
+            .next_if(|info| *info.key == profile_key)
+            .ok_or(ErrorCode::ProfileRequired)?;
+        let mut profile = load_or_create_profile(
+            profile_info,
+            &author_key,
+            profile_bump,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        let index_info = remaining_accounts
+            .next()
+            .ok_or(ErrorCode::IndexPageMismatch)?;
+        let (recent_key, _) = recent_post_address(&author_key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            &mut profile,
+            remaining_accounts.next_if(|info| *info.key == recent_key),
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        profile.tweet_count.increment()?;
+        append_to_author_index(
+            &mut profile,
+            tweet.key(),
+            index_info,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        store_account(profile_info, &profile)?;
+
+        if !tweet.topic.is_empty() {
+            let (topic_key, bump) = topic_index_address(&tweet.topic, ctx.program_id);
+            if let Some(topic_info) = remaining_accounts.next_if(|info| *info.key == topic_key) {
+                add_to_topic_index(
+                    &tweet.topic,
+                    tweet.key(),
+                    topic_info,
+                    bump,
+                    &author.to_account_info(),
+                    &ctx.accounts.system_program,
+                    ctx.program_id,
+                )?;
+            }
+        }
+
+        record_hashtags(
+            &tweet.content,
+            &mut remaining_accounts,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        record_mentions(
+            tweet,
+            &mut remaining_accounts,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        record_stat(
+            Stat::Tweet,
+            ctx.remaining_accounts,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            &clock,
+            ctx.program_id,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateTweet<'info>>,
+        topic: String,
+        content: String,
+        reply_permission: u8,
+        media: Vec<MediaAttachment>,
+    ) -> Result<()> {
         let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
         let author: &Signer = &ctx.accounts.author;
-        let clock: Clock = Clock::get().unwrap();
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+        // A delegate editing on behalf of the author passes its Delegate PDA first.
+        let mut remaining_accounts = ctx.remaining_accounts.iter().peekable();
+
+        if tweet.version < TWEET_VERSION {
+            return Err(error!(ErrorCode::AccountVersionTooOld));
+        }
+
+        ensure_author_or_delegate(
+            &tweet.author,
+            author.key,
+            &mut remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_not_paused(config)?;
 
-        if topic.chars().count() > 50 {
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+
+        if topic.chars().count() > config.max_topic_len as usize {
             return Err(error!(ErrorCode::TopicTooLong));
         }
 
-        if content.chars().count() > 280 {
+        validate_media(&media)?;
+
+        if content.chars().count() > config.max_content_len as usize {
             return Err(error!(ErrorCode::ContentTooLong));
         }
 
-        tweet.author = *author.key;
-        tweet.timestamp = clock.unix_timestamp;
+        // The account is resized to what `send_tweet` would allocate for the new media, plus
+        // the space for content beyond the regular maximum. That space is kept when the new
+        // content is shorter, it may have been bought through `realloc_tweet`. The preview is
+        // dropped below so its space is released. Like all of the tweet's rent, growing is
+        // paid by and shrinking refunds whoever posted it.
+        let tweet_info: AccountInfo = tweet.to_account_info();
+        let new_space = Tweet::LEN
+            + Tweet::media_space(&media)
+            + content
+                .len()
+                .saturating_sub(MAX_CONTENT_LENGTH)
+                .max(tweet.extra_content_space(tweet_info.data_len()));
+        if new_space > tweet_info.data_len() {
+            grow_tweet(
+                &tweet_info,
+                &ctx.accounts.posted_by.to_account_info(),
+                &ctx.accounts.system_program,
+                new_space,
+            )?;
+        } else if new_space < tweet_info.data_len() {
+            shrink_account(
+                &tweet_info,
+                &ctx.accounts.posted_by.to_account_info(),
+                new_space,
+            )?;
+        }
+        tweet.media = media;
+        // Edits follow the regular content limits, so a stale preview is dropped.
+        tweet.preview = String::new();
+
+        if reply_permission > REPLY_NOBODY {
+            return Err(error!(ErrorCode::InvalidReplyPermission));
+        }
+
+        if tweet.revision_count as usize >= MAX_REVISIONS {
+            return Err(error!(ErrorCode::TooManyEdits));
+        }
+
+        // Keep the previous version around so edits are never silent.
+        let revision: &mut Account<TweetRevision> = &mut ctx.accounts.revision;
+        revision.tweet = tweet.key();
+        revision.author = tweet.author;
+        revision.index = tweet.revision_count;
+        revision.topic = tweet.topic.clone();
+        revision.content = tweet.content.clone();
+        revision.edited_at = clock.unix_timestamp;
+        revision.bump = *ctx.bumps.get("revision").unwrap();
+        tweet.revision_count.increment()?;
+
+        move_topic_index(
+            tweet.key(),
+            &tweet.topic,
+            &topic,
+            &mut remaining_accounts,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        // Log the hash of the replaced content, a tamper-evident trail that outlives the
+        // revisions closed by the author.
+        let edit_log: &mut Account<EditLog> = &mut ctx.accounts.edit_log;
+        edit_log.tweet = tweet.key();
+        edit_log.bump = *ctx.bumps.get("edit_log").unwrap();
+        edit_log.append(EditLogEntry {
+            previous_content_hash: tweet.content_hash,
+            edited_at: clock.unix_timestamp,
+        })?;
+
         tweet.topic = topic;
+        tweet.content_hash = hash(content.as_bytes()).to_bytes();
         tweet.content = content;
+        tweet.edited_at = Some(clock.unix_timestamp);
+        tweet.reply_permission = reply_permission;
+
+        emit!(TweetUpdated {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a leaf tweet and refunds its rent to whoever paid for it, the author or the
+    /// delegate who posted it, whichever of them signs. Tweets with replies should go through
+    /// `soft_delete_tweet` instead so their replies keep resolving their parent.
+    pub fn delete_tweet(ctx: Context<DeleteTweet>) -> Result<()> {
+        let tweet: &Account<Tweet> = &ctx.accounts.tweet;
+        let mut remaining_accounts = ctx.remaining_accounts.iter().peekable();
+
+        // A delegate deleting on behalf of the author passes its Delegate PDA first.
+        ensure_author_or_delegate(
+            &tweet.author,
+            ctx.accounts.author.key,
+            &mut remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        // Deleting a reply requires its parent tweet next, to decrement its reply count.
+        // Top-level tweets have no parent so they never need that account.
+        if let Some(parent_key) = tweet.reply_to {
+            let parent_info = remaining_accounts
+                .next_if(|info| *info.key == parent_key)
+                .ok_or(ErrorCode::ParentTweetRequired)?;
+            release_parent(parent_info, ctx.program_id)?;
+        }
+
+        // The author's profile can be passed next so a pin on the tweet does not dangle.
+        let (profile_key, _) =
+            Pubkey::find_program_address(&[PROFILE_SEED, tweet.author.as_ref()], ctx.program_id);
+        if let Some(profile_info) = remaining_accounts.next_if(|info| *info.key == profile_key) {
+            clear_pin(&tweet.key(), profile_info, ctx.program_id)?;
+        }
+
+        // The AuthorIndex page holding the tweet can be passed to tombstone its slot.
+        if let Some(index_info) = remaining_accounts.next() {
+            tombstone_index_slot(tweet, index_info, ctx.program_id)?;
+        }
+
+        emit!(TweetDeleted {
+            tweet: tweet.key(),
+            author: tweet.author,
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the GlobalStats and today's DailyStats, both optional.
+    pub fn create_profile<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateProfile<'info>>,
+        display_name: String,
+        bio: String,
+        avatar_uri: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let authority: &Signer = &ctx.accounts.authority;
+        let clock: Clock = Clock::get()?;
+
+        if display_name.chars().count() > 32 {
+            return Err(error!(ErrorCode::DisplayNameTooLong));
+        }
+
+        if bio.chars().count() > 160 {
+            return Err(error!(ErrorCode::BioTooLong));
+        }
+
+        if avatar_uri.chars().count() > 200 {
+            return Err(error!(ErrorCode::AvatarUriTooLong));
+        }
+
+        **profile = UserProfile {
+            display_name,
+            bio,
+            avatar_uri,
+            ..UserProfile::new(
+                *authority.key,
+                clock.unix_timestamp,
+                *ctx.bumps.get("profile").unwrap(),
+            )
+        };
+
+        record_stat(
+            Stat::User,
+            ctx.remaining_accounts,
+            &authority.to_account_info(),
+            &ctx.accounts.system_program,
+            &clock,
+            ctx.program_id,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_profile(
+        ctx: Context<UpdateProfile>,
+        display_name: String,
+        bio: String,
+        avatar_uri: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+
+        if display_name.chars().count() > 32 {
+            return Err(error!(ErrorCode::DisplayNameTooLong));
+        }
+
+        if bio.chars().count() > 160 {
+            return Err(error!(ErrorCode::BioTooLong));
+        }
+
+        if avatar_uri.chars().count() > 200 {
+            return Err(error!(ErrorCode::AvatarUriTooLong));
+        }
+
+        profile.display_name = display_name;
+        profile.bio = bio;
+        profile.avatar_uri = avatar_uri;
+
+        Ok(())
+    }
+
+    /// Closes a profile and refunds its rent. The follows of other users are theirs to close so
+    /// they are left dangling. The tweet count also numbers indexed tweets and never goes
+    /// down, so only a profile that never tweeted can be closed.
+    pub fn close_profile(_ctx: Context<CloseProfile>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Lets the admin close a profile that has not posted for longer than the inactivity
+    /// window. A profile that never posted counts from its creation. Like `close_profile`,
+    /// only a profile that never tweeted can be closed: a recreated profile would number its
+    /// indexed tweets from 0 again and run into the addresses of the old ones. The rent goes
+    /// back to the profile's authority rather than to the admin.
+    pub fn reclaim_profile(ctx: Context<ReclaimProfile>) -> Result<()> {
+        let profile: &Account<UserProfile> = &ctx.accounts.profile;
+        let clock: Clock = Clock::get()?;
+
+        let last_activity = profile.last_post_ts.max(profile.created_at);
+        if clock.unix_timestamp - last_activity <= ctx.accounts.config.inactivity_window {
+            return Err(error!(ErrorCode::ProfileStillActive));
+        }
+
+        Ok(())
+    }
+
+    /// Hides a profile from new interactions until it is reactivated.
+    pub fn deactivate_profile(ctx: Context<UpdateProfile>) -> Result<()> {
+        ctx.accounts.profile.active = false;
+
+        Ok(())
+    }
+
+    pub fn reactivate_profile(ctx: Context<UpdateProfile>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        ctx.accounts.profile.active = true;
 
         Ok(())
     }
 
-    pub fn update_tweet(ctx: Context<UpdateTweet>, topic: String, content: String) -> Result<()> {
+    /// Likes a tweet. Liking a token-gated tweet requires holding its gate token.
+    pub fn like_tweet<'info>(ctx: Context<'_, '_, '_, 'info, LikeTweet<'info>>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let like: &mut Account<Like> = &mut ctx.accounts.like;
         let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let liker: &Signer = &ctx.accounts.liker;
+        let clock: Clock = Clock::get()?;
 
-        if topic.chars().count() > 50 {
-            return Err(ErrorCode::TopicTooLong.into());
+        if tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
         }
 
-        if content.chars().count() > 280 {
-            return Err(ErrorCode::ContentTooLong.into());
+        ensure_not_blocked(
+            &tweet.author,
+            liker.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_token_holder(tweet, liker.key, ctx.remaining_accounts)?;
+
+        like.tweet = tweet.key();
+        like.liker = *liker.key;
+        like.timestamp = clock.unix_timestamp;
+        like.bump = *ctx.bumps.get("like").unwrap();
+
+        tweet.like_count.increment()?;
+
+        // Remaining accounts are the Block PDA proving the liker is not blocked, the liker's
+        // profile, created when they have none yet, the GlobalStats and today's DailyStats.
+        // All are optional, as is the liker's token account which is only required by
+        // token-gated tweets.
+        let (profile_key, profile_bump) =
+            Pubkey::find_program_address(&[PROFILE_SEED, liker.key.as_ref()], ctx.program_id);
+        if let Some(profile_info) = ctx
+            .remaining_accounts
+            .iter()
+            .find(|info| *info.key == profile_key)
+        {
+            load_or_create_profile(
+                profile_info,
+                liker.key,
+                profile_bump,
+                &liker.to_account_info(),
+                &ctx.accounts.system_program,
+                ctx.program_id,
+            )?;
         }
+        ctx.accounts.author_profile.likes_received.increment()?;
+        record_stat(
+            Stat::Like,
+            ctx.remaining_accounts,
+            &liker.to_account_info(),
+            &ctx.accounts.system_program,
+            &clock,
+            ctx.program_id,
+        )?;
 
-        tweet.topic = topic;
-        tweet.content = content;
+        emit!(TweetLiked {
+            tweet: tweet.key(),
+            liker: like.liker,
+            timestamp: like.timestamp,
+        });
 
         Ok(())
     }
 
-    pub fn delete_tweet(_ctx: Context<DeleteTweet>) -> Result<()> {
+    /// Likes a tweet and locks `amount` lamports in the Like PDA to weigh the like. The
+    /// stake is refunded to the liker along with the rent when they unlike the tweet.
+    pub fn stake_like<'info>(
+        ctx: Context<'_, '_, '_, 'info, LikeTweet<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let like_info: AccountInfo = ctx.accounts.like.to_account_info();
+        let liker: &Signer = &ctx.accounts.liker;
+
+        if amount == 0 {
+            return Err(error!(ErrorCode::InvalidStakeAmount));
+        }
+
+        invoke(
+            &system_instruction::transfer(liker.key, like_info.key, amount),
+            &[
+                liker.to_account_info(),
+                like_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        ctx.accounts.like.staked = amount;
+        ctx.accounts.tweet.weighted_like_score = ctx
+            .accounts
+            .tweet
+            .weighted_like_score
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        like_tweet(ctx)
+    }
+
+    pub fn unlike_tweet(ctx: Context<UnlikeTweet>) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.like_count.decrement()?;
+        // Closing the Like PDA sends the stake back to the liker along with the rent.
+        tweet.weighted_like_score = tweet
+            .weighted_like_score
+            .checked_sub(ctx.accounts.like.staked)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        // Likes from before the author's profile was required were not counted, so they are
+        // not taken back either.
+        let likes_received = &mut ctx.accounts.author_profile.likes_received;
+        *likes_received = likes_received.saturating_sub(1);
+
+        emit!(TweetUnliked {
+            tweet: tweet.key(),
+            liker: ctx.accounts.liker.key(),
+        });
+
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct SendTweet<'info> {
-    #[account(init, payer = author, space = Tweet::LEN)]
-    pub tweet: Account<'info, Tweet>,
-    #[account(mut)]
-    pub author: Signer<'info>,
-    #[account(address = system_program::ID)]
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub system_program: AccountInfo<'info>,
-}
+    pub fn send_comment(ctx: Context<SendComment>, content: String) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
 
-#[derive(Accounts)]
-pub struct UpdateTweet<'info> {
-    #[account(mut, has_one = author)]
-    pub tweet: Account<'info, Tweet>,
-    pub author: Signer<'info>,
-}
+        let comment: &mut Account<Comment> = &mut ctx.accounts.comment;
+        let parent_tweet: &mut Account<Tweet> = &mut ctx.accounts.parent_tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let clock: Clock = Clock::get()?;
 
-#[derive(Accounts)]
-pub struct DeleteTweet<'info> {
-    #[account(mut, has_one = author, close = author)]
-    pub tweet: Account<'info, Tweet>,
-    pub author: Signer<'info>,
-}
+        // Comments have no topic but share the content limit of tweets.
+        ensure_tweet_limits("", &content, &ctx.accounts.config)?;
 
-#[account]
-pub struct Tweet {
-    pub author: Pubkey,
-    pub timestamp: i64,
-    pub topic: String,
-    pub content: String,
-}
+        if parent_tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
 
-const DISCRIMINATOR_LENGTH: usize = 8;
-const PUBLIC_KEY_LENGTH: usize = 32;
-const TIMESTAMP_LENGTH: usize = 8;
-const STRING_LENGTH_PREFIX: usize = 4; // Stores the size of the string.
-const MAX_TOPIC_LENGTH: usize = 50 * 4; // 50 chars max.
-const MAX_CONTENT_LENGTH: usize = 280 * 4; // 280 chars max.
+        ensure_not_blocked(
+            &parent_tweet.author,
+            author.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_reply_allowed(
+            parent_tweet,
+            author.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
 
-impl Tweet {
-    const LEN: usize = DISCRIMINATOR_LENGTH
-        + PUBLIC_KEY_LENGTH // Author.
-        + TIMESTAMP_LENGTH // Timestamp.
-        + STRING_LENGTH_PREFIX + MAX_TOPIC_LENGTH // Topic.
-        + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH; // Content.
+        // Deactivated profiles cannot comment.
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        ensure_profile_active(profile)?;
+
+        comment.author = *author.key;
+        comment.parent_tweet = parent_tweet.key();
+        comment.timestamp = clock.unix_timestamp;
+        comment.content = content;
+
+        parent_tweet.comments.increment()?;
+
+        Ok(())
+    }
+
+    pub fn delete_comment(ctx: Context<DeleteComment>) -> Result<()> {
+        let parent_tweet: &mut Account<Tweet> = &mut ctx.accounts.parent_tweet;
+
+        parent_tweet.comments.decrement()?;
+
+        Ok(())
+    }
+
+    pub fn follow_user(ctx: Context<FollowUser>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let follow: &mut Account<Follow> = &mut ctx.accounts.follow;
+        let follower_profile: &mut Account<UserProfile> = &mut ctx.accounts.follower_profile;
+        let followed_profile: &mut Account<UserProfile> = &mut ctx.accounts.followed_profile;
+        let follower: &Signer = &ctx.accounts.follower;
+        let clock: Clock = Clock::get()?;
+
+        if followed_profile.authority == *follower.key {
+            return Err(error!(ErrorCode::CannotFollowSelf));
+        }
+
+        fill_new_profile(
+            follower_profile,
+            *follower.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("follower_profile").unwrap(),
+        );
+
+        ensure_profile_active(follower_profile)?;
+        ensure_profile_active(followed_profile)?;
+
+        follow.follower = *follower.key;
+        follow.followed = followed_profile.authority;
+        follow.timestamp = clock.unix_timestamp;
+        follow.bump = *ctx.bumps.get("follow").unwrap();
+
+        follower_profile.following.increment()?;
+        followed_profile.followers.increment()?;
+
+        Ok(())
+    }
+
+    pub fn unfollow_user(ctx: Context<UnfollowUser>) -> Result<()> {
+        let follower_profile: &mut Account<UserProfile> = &mut ctx.accounts.follower_profile;
+        let followed_profile: &mut Account<UserProfile> = &mut ctx.accounts.followed_profile;
+
+        follower_profile.following.decrement()?;
+        followed_profile.followers.decrement()?;
+
+        Ok(())
+    }
+
+    /// Replies to a tweet. Replying to a token-gated tweet requires holding its gate token.
+    /// The author's RecentPost can be passed among the remaining accounts, it is optional
+    /// unless a cooldown is active.
+    pub fn reply<'info>(
+        ctx: Context<'_, '_, '_, 'info, Reply<'info>>,
+        topic: String,
+        content: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let parent: &mut Account<Tweet> = &mut ctx.accounts.parent;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let clock: Clock = Clock::get()?;
+
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, &ctx.accounts.config)?;
+
+        if parent.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        ensure_not_blocked(
+            &parent.author,
+            author.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_reply_allowed(parent, author.key, ctx.remaining_accounts, ctx.program_id)?;
+        ensure_token_holder(parent, author.key, ctx.remaining_accounts)?;
+
+        **tweet = Tweet {
+            reply_to: Some(parent.key()),
+            // Replies to a reply belong to the same thread, the top-level tweet is its root.
+            thread_root: Some(parent.thread_root.unwrap_or_else(|| parent.key())),
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                topic,
+                content,
+                clock.unix_timestamp,
+            )
+        };
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        parent.reply_count.increment()?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn retweet(ctx: Context<RetweetTweet>, quote: Option<String>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let retweet: &mut Account<Retweet> = &mut ctx.accounts.retweet;
+        let original_tweet: &mut Account<Tweet> = &mut ctx.accounts.original_tweet;
+        let retweeter: &Signer = &ctx.accounts.retweeter;
+        let clock: Clock = Clock::get()?;
+
+        if original_tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        ensure_not_blocked(
+            &original_tweet.author,
+            retweeter.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        // A quote has no topic of its own, it follows the content limits of a tweet.
+        if let Some(quote) = &quote {
+            ensure_tweet_limits("", quote, &ctx.accounts.config)?;
+        }
+
+        retweet.original_tweet = original_tweet.key();
+        retweet.retweeter = *retweeter.key;
+        retweet.timestamp = clock.unix_timestamp;
+        retweet.quote = quote;
+        retweet.bump = *ctx.bumps.get("retweet").unwrap();
+
+        // Quotes and plain retweets are counted apart, the retweet PDA still allows only
+        // one of them per user.
+        if retweet.quote.is_some() {
+            original_tweet.quote_count.increment()?;
+        } else {
+            original_tweet.retweet_count.increment()?;
+        }
+
+        emit!(TweetRetweeted {
+            original_tweet: original_tweet.key(),
+            retweeter: retweet.retweeter,
+            timestamp: retweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn undo_retweet(ctx: Context<UndoRetweet>) -> Result<()> {
+        let original_tweet: &mut Account<Tweet> = &mut ctx.accounts.original_tweet;
+
+        if ctx.accounts.retweet.quote.is_some() {
+            original_tweet.quote_count.decrement()?;
+        } else {
+            original_tweet.retweet_count.decrement()?;
+        }
+
+        emit!(RetweetUndone {
+            original_tweet: original_tweet.key(),
+            retweeter: ctx.accounts.retweeter.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn quote_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, QuoteTweet<'info>>,
+        content: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let quoted_tweet: &mut Account<Tweet> = &mut ctx.accounts.quoted_tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let clock: Clock = Clock::get()?;
+
+        let (_, content) = normalize_tweet("", &content)?;
+        ensure_tweet_limits("", &content, &ctx.accounts.config)?;
+
+        if quoted_tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        ensure_not_blocked(
+            &quoted_tweet.author,
+            author.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        **tweet = Tweet {
+            quoted_tweet: Some(quoted_tweet.key()),
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                String::new(),
+                content,
+                clock.unix_timestamp,
+            )
+        };
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        quoted_tweet.quote_count.increment()?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the owner's profile, optional, to display the handle on it.
+    pub fn register_handle(ctx: Context<RegisterHandle>, handle: String) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let handle_account: &mut Account<Handle> = &mut ctx.accounts.handle_account;
+        let owner: &Signer = &ctx.accounts.owner;
+
+        if handle.len() < 3 {
+            return Err(error!(ErrorCode::HandleTooShort));
+        }
+
+        if handle.len() > 15 {
+            return Err(error!(ErrorCode::HandleTooLong));
+        }
+
+        if !handle
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(error!(ErrorCode::InvalidHandleCharacter));
+        }
+
+        handle_account.owner = *owner.key;
+        handle_account.handle = handle;
+        handle_account.bump = *ctx.bumps.get("handle_account").unwrap();
+
+        link_profile_handle(
+            owner.key,
+            &handle_account.handle,
+            true,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )
+    }
+
+    /// Remaining accounts are the owner's profile, optional, to remove the handle from it.
+    pub fn release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+        link_profile_handle(
+            ctx.accounts.owner.key,
+            &ctx.accounts.handle_account.handle,
+            false,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )
+    }
+
+    /// Remaining accounts are the current owner's profile, optional, to remove the handle
+    /// from it. The new owner links the handle to their own profile themselves.
+    pub fn transfer_handle(ctx: Context<TransferHandle>, new_owner: Pubkey) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let handle_account: &mut Account<Handle> = &mut ctx.accounts.handle_account;
+
+        link_profile_handle(
+            ctx.accounts.owner.key,
+            &handle_account.handle,
+            false,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        handle_account.owner = new_owner;
+
+        Ok(())
+    }
+
+    /// Tips the author of a tweet. Tipping a token-gated tweet requires holding its gate token.
+    /// The co-authors of the tweet get their share of the tip and must be passed first in the
+    /// remaining accounts, in the order the tweet declares them.
+    pub fn tip_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, TipTweet<'info>>,
+        lamports: u64,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let tipper: &Signer = &ctx.accounts.tipper;
+        let author: &SystemAccount = &ctx.accounts.author;
+
+        if tweet.is_expired(&Clock::get()?) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        if lamports == 0 {
+            return Err(error!(ErrorCode::ZeroTip));
+        }
+
+        if tweet.author == *tipper.key {
+            return Err(error!(ErrorCode::CannotTipOwnTweet));
+        }
+
+        ensure_not_blocked(
+            &tweet.author,
+            tipper.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_token_holder(tweet, tipper.key, ctx.remaining_accounts)?;
+
+        // Co-authors come first in the remaining accounts, in the order the tweet declares
+        // them, and get their share rounded down so the dust stays with the author.
+        let mut author_share = lamports;
+        for (i, co_author) in tweet.co_authors.iter().enumerate() {
+            let co_author_info = ctx
+                .remaining_accounts
+                .get(i)
+                .filter(|info| *info.key == co_author.key)
+                .ok_or(ErrorCode::CoAuthorMismatch)?;
+            let share = (lamports as u128 * co_author.bps as u128 / MAX_BPS as u128) as u64;
+            if share == 0 {
+                continue;
+            }
+
+            invoke(
+                &system_instruction::transfer(tipper.key, co_author_info.key, share),
+                &[
+                    tipper.to_account_info(),
+                    co_author_info.clone(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+            author_share -= share;
+        }
+
+        if author_share > 0 {
+            invoke(
+                &system_instruction::transfer(tipper.key, &author.key(), author_share),
+                &[
+                    tipper.to_account_info(),
+                    author.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        tweet.tips_received = tweet
+            .tips_received
+            .checked_add(lamports)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn pin_tweet(ctx: Context<PinTweet>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let tweet: &Account<Tweet> = &ctx.accounts.tweet;
+
+        if tweet.author != profile.authority {
+            return Err(error!(ErrorCode::CannotPinOthersTweet));
+        }
+
+        profile.pinned_tweet = Some(tweet.key());
+
+        Ok(())
+    }
+
+    pub fn unpin_tweet(ctx: Context<UnpinTweet>) -> Result<()> {
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+
+        profile.pinned_tweet = None;
+
+        Ok(())
+    }
+
+    /// Tips the author of a tweet in SPL tokens. Unlike `tip_tweet` the tip is not split with
+    /// the co-authors, the whole amount goes to the author's token account. Tipping a
+    /// token-gated tweet requires holding its gate token.
+    pub fn tip_tweet_spl(ctx: Context<TipTweetSpl>, amount: u64) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let tip_stats: &mut Account<TipStats> = &mut ctx.accounts.tip_stats;
+        let tipper: &Signer = &ctx.accounts.tipper;
+
+        if tweet.is_expired(&Clock::get()?) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        if amount == 0 {
+            return Err(error!(ErrorCode::ZeroTip));
+        }
+
+        if tweet.author == *tipper.key {
+            return Err(error!(ErrorCode::CannotTipOwnTweet));
+        }
+
+        ensure_not_blocked(
+            &tweet.author,
+            tipper.key,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        ensure_token_holder(tweet, tipper.key, ctx.remaining_accounts)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.tipper_token_account.to_account_info(),
+                    to: ctx.accounts.author_token_account.to_account_info(),
+                    authority: tipper.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        tweet.total_tips_spl = tweet
+            .total_tips_spl
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+
+        tip_stats.tweet = tweet.key();
+        tip_stats.mint = ctx.accounts.mint.key();
+        tip_stats.total_amount = tip_stats
+            .total_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        tip_stats.tip_count.increment()?;
+        tip_stats.bump = *ctx.bumps.get("tip_stats").unwrap();
+
+        Ok(())
+    }
+
+    /// Sends a tweet at a PDA derived from the author and their profile's tweet count, so
+    /// clients can fetch the n-th tweet of an author without scanning the program accounts.
+    /// Indexes shared with `send_tweet` have no PDA, so pagination should skip missing tweets.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active,
+    /// followed by one Hashtag PDA per distinct hashtag.
+    pub fn send_indexed_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendIndexedTweet<'info>>,
+        topic: String,
+        content: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let clock: Clock = Clock::get()?;
+
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, &ctx.accounts.config)?;
+
+        **tweet = Tweet {
+            author_index: Some(profile.tweet_count),
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                topic,
+                content,
+                clock.unix_timestamp,
+            )
+        };
+        let mut remaining_accounts = ctx.remaining_accounts.iter().peekable();
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            remaining_accounts.next_if(|info| *info.key == recent_key),
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        // Overflowing would wrap the index back to an existing tweet PDA, so we fail instead.
+        profile.tweet_count.increment()?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        record_hashtags(
+            &tweet.content,
+            &mut remaining_accounts,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn bookmark_tweet(ctx: Context<BookmarkTweet>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let bookmark: &mut Account<Bookmark> = &mut ctx.accounts.bookmark;
+        let clock: Clock = Clock::get()?;
+
+        // Bookmarks are private so, unlike likes, no counter is updated on the tweet.
+        bookmark.user = ctx.accounts.user.key();
+        bookmark.tweet = ctx.accounts.tweet.key();
+        bookmark.timestamp = clock.unix_timestamp;
+        bookmark.bump = *ctx.bumps.get("bookmark").unwrap();
+
+        Ok(())
+    }
+
+    pub fn remove_bookmark(_ctx: Context<RemoveBookmark>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn transfer_tweet_ownership(
+        ctx: Context<TransferOwnership>,
+        new_author: Pubkey,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        // Indexed tweets live at a PDA derived from their author so their address would
+        // no longer match the new author. Only keypair tweets can change hands.
+        if tweet.author_index.is_some() {
+            return Err(error!(ErrorCode::CannotTransferPdaTweet));
+        }
+
+        tweet.author = new_author;
+
+        Ok(())
+    }
+
+    pub fn create_poll(
+        ctx: Context<CreatePoll>,
+        question: String,
+        options: Vec<String>,
+        duration_secs: i64,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let poll: &mut Account<Poll> = &mut ctx.accounts.poll;
+        let clock: Clock = Clock::get()?;
+
+        // The question is shown as the poll's tweet, so it has the same limits.
+        ensure_tweet_limits("", &question, &ctx.accounts.config)?;
+
+        if options.len() < 2 || options.len() > 4 {
+            return Err(error!(ErrorCode::InvalidPollOptionCount));
+        }
+
+        if options.iter().any(|option| option.chars().count() > 50) {
+            return Err(error!(ErrorCode::PollOptionTooLong));
+        }
+
+        if duration_secs <= 0 {
+            return Err(error!(ErrorCode::InvalidPollDuration));
+        }
+
+        poll.tweet = ctx.accounts.tweet.key();
+        poll.author = ctx.accounts.author.key();
+        poll.question = question;
+        poll.votes = vec![0; options.len()];
+        poll.options = options;
+        poll.timestamp = clock.unix_timestamp;
+        poll.ends_at = clock
+            .unix_timestamp
+            .checked_add(duration_secs)
+            .ok_or(ErrorCode::InvalidPollDuration)?;
+        poll.bump = *ctx.bumps.get("poll").unwrap();
+
+        Ok(())
+    }
+
+    pub fn vote_poll(ctx: Context<VotePoll>, option_index: u8) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let poll: &mut Account<Poll> = &mut ctx.accounts.poll;
+        let poll_vote: &mut Account<PollVote> = &mut ctx.accounts.poll_vote;
+        let clock: Clock = Clock::get()?;
+
+        if clock.unix_timestamp >= poll.ends_at {
+            return Err(error!(ErrorCode::PollClosed));
+        }
+
+        let votes = poll
+            .votes
+            .get_mut(option_index as usize)
+            .ok_or(ErrorCode::InvalidOption)?;
+        votes.increment()?;
+
+        poll_vote.poll = poll.key();
+        poll_vote.voter = ctx.accounts.voter.key();
+        poll_vote.option_index = option_index;
+        poll_vote.timestamp = clock.unix_timestamp;
+        poll_vote.bump = *ctx.bumps.get("poll_vote").unwrap();
+
+        Ok(())
+    }
+
+    pub fn block_user(ctx: Context<BlockUser>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let block: &mut Account<Block> = &mut ctx.accounts.block;
+        let blocker: &Signer = &ctx.accounts.blocker;
+        let clock: Clock = Clock::get()?;
+
+        if ctx.accounts.blocked.key() == *blocker.key {
+            return Err(error!(ErrorCode::CannotBlockSelf));
+        }
+
+        block.blocker = *blocker.key;
+        block.blocked = ctx.accounts.blocked.key();
+        block.timestamp = clock.unix_timestamp;
+        block.bump = *ctx.bumps.get("block").unwrap();
+
+        Ok(())
+    }
+
+    pub fn unblock_user(_ctx: Context<UnblockUser>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
+        config.paused = false;
+        config.max_content_len = 280;
+        config.max_topic_len = 50;
+        config.duplicate_window_secs = 60;
+        config.report_threshold = 5;
+        config.post_fee_lamports = 0;
+        config.tweet_cooldown_secs = 0;
+        config.min_post_interval = 0;
+        config.gate_mint = None;
+        config.inactivity_window = DEFAULT_INACTIVITY_WINDOW;
+        config.oracle = Pubkey::default();
+        config.bump = *ctx.bumps.get("config").unwrap();
+
+        ctx.accounts.treasury.bump = *ctx.bumps.get("treasury").unwrap();
+        ctx.accounts.stats.bump = *ctx.bumps.get("stats").unwrap();
+
+        Ok(())
+    }
+
+    /// Verification lives on the profile rather than on a handle so it stays with its
+    /// holder when their handles change hands.
+    pub fn set_verified(ctx: Context<SetVerified>, value: bool) -> Result<()> {
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+
+        profile.verified = value;
+
+        emit!(VerificationChanged {
+            profile: profile.key(),
+            authority: profile.authority,
+            verified: value,
+        });
+
+        Ok(())
+    }
+
+    /// First step of an admin transfer, it only takes effect once the new admin accepts it
+    /// so a typo in the address cannot lock everyone out.
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        if new_admin == Pubkey::default() {
+            return Err(error!(ErrorCode::InvalidAdmin));
+        }
+
+        config.pending_admin = Some(new_admin);
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.paused = paused;
+
+        Ok(())
+    }
+
+    pub fn set_report_threshold(ctx: Context<UpdateConfig>, report_threshold: u32) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        if report_threshold == 0 {
+            return Err(error!(ErrorCode::InvalidConfig));
+        }
+
+        config.report_threshold = report_threshold;
+
+        Ok(())
+    }
+
+    pub fn set_post_fee(ctx: Context<UpdateConfig>, post_fee_lamports: u64) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.post_fee_lamports = post_fee_lamports;
+
+        Ok(())
+    }
+
+    /// Withdraws posting fees from the treasury to a destination picked by the admin. The
+    /// treasury keeps its rent-exempt minimum so it can never be garbage collected.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, lamports: u64) -> Result<()> {
+        let treasury_info: AccountInfo = ctx.accounts.treasury.to_account_info();
+        let destination_info: AccountInfo = ctx.accounts.destination.to_account_info();
+
+        let rent_lamports = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info.lamports().saturating_sub(rent_lamports);
+        if lamports > available {
+            return Err(error!(ErrorCode::InsufficientTreasuryFunds));
+        }
+
+        let destination_lamports = destination_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        **treasury_info.try_borrow_mut_lamports()? -= lamports;
+        **destination_info.try_borrow_mut_lamports()? = destination_lamports;
+
+        Ok(())
+    }
+
+    pub fn set_tweet_cooldown(ctx: Context<UpdateConfig>, tweet_cooldown_secs: u32) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.tweet_cooldown_secs = tweet_cooldown_secs;
+
+        Ok(())
+    }
+
+    /// Sets the minimum number of seconds between two tweets of a profile, 0 disables it.
+    pub fn set_min_post_interval(ctx: Context<UpdateConfig>, min_post_interval: i64) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        if min_post_interval < 0 {
+            return Err(error!(ErrorCode::InvalidPostInterval));
+        }
+
+        config.min_post_interval = min_post_interval;
+
+        Ok(())
+    }
+
+    /// Sets the mint whose holders can use `send_gated_tweet`, `None` closes gated posting.
+    pub fn set_gate_mint(ctx: Context<UpdateConfig>, gate_mint: Option<Pubkey>) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.gate_mint = gate_mint;
+
+        Ok(())
+    }
+
+    /// Sets how many seconds a profile has to be inactive before `reclaim_profile` can
+    /// close it.
+    pub fn set_inactivity_window(ctx: Context<UpdateConfig>, inactivity_window: i64) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        if inactivity_window <= 0 {
+            return Err(error!(ErrorCode::InvalidInactivityWindow));
+        }
+
+        config.inactivity_window = inactivity_window;
+
+        Ok(())
+    }
+
+    /// Sets who can call `record_impressions`, the default key disables it.
+    pub fn set_oracle(ctx: Context<UpdateConfig>, oracle: Pubkey) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.oracle = oracle;
+
+        Ok(())
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        max_content_len: u16,
+        max_topic_len: u16,
+        duplicate_window_secs: i64,
+    ) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        // Topics are sized for 50 chars. Content beyond 280 chars grows the tweet on edit or
+        // through `realloc_tweet`.
+        if max_content_len == 0
+            || max_content_len > MAX_CONFIGURABLE_CONTENT_CHARS
+            || max_topic_len > 50
+            || duplicate_window_secs < 0
+        {
+            return Err(error!(ErrorCode::InvalidConfig));
+        }
+
+        config.max_content_len = max_content_len;
+        config.max_topic_len = max_topic_len;
+        config.duplicate_window_secs = duplicate_window_secs;
+
+        Ok(())
+    }
+
+    /// Tombstones a tweet without closing its account, so threads stay intact.
+    pub fn soft_delete_tweet(ctx: Context<SoftDeleteTweet>) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.is_deleted = true;
+        tweet.topic = String::new();
+        tweet.content = String::new();
+
+        emit!(TweetDeleted {
+            tweet: tweet.key(),
+            author: tweet.author,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a soft-deleted tweet once all its replies are gone and refunds its rent to
+    /// whoever posted it. A soft-deleted reply can pass its parent tweet to decrement its
+    /// reply count.
+    pub fn reclaim_soft_deleted(ctx: Context<ReclaimSoftDeleted>) -> Result<()> {
+        let tweet: &Account<Tweet> = &ctx.accounts.tweet;
+
+        if let Some(parent_key) = tweet.reply_to {
+            if let Some(parent_info) = ctx
+                .remaining_accounts
+                .iter()
+                .find(|info| *info.key == parent_key)
+            {
+                let mut parent: Tweet = load_account(parent_info, ctx.program_id)?;
+                parent.reply_count.decrement()?;
+                store_account(parent_info, &parent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<TransferAdmin>) -> Result<()> {
+        let config: &mut Account<Config> = &mut ctx.accounts.config;
+
+        config.pending_admin = None;
+
+        Ok(())
+    }
+
+    pub fn report_tweet(ctx: Context<ReportTweet>, reason: u8) -> Result<()> {
+        let report: &mut Account<Report> = &mut ctx.accounts.report;
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        if reason >= REPORT_REASON_COUNT {
+            return Err(error!(ErrorCode::InvalidReportReason));
+        }
+
+        report.reporter = ctx.accounts.reporter.key();
+        report.tweet = tweet.key();
+        report.reason = reason;
+        report.timestamp = clock.unix_timestamp;
+        report.bump = *ctx.bumps.get("report").unwrap();
+
+        tweet.reports.increment()?;
+
+        // Only notify moderators once, when the threshold is first reached.
+        if tweet.reports == config.report_threshold {
+            emit!(TweetFlaggedForReview {
+                tweet: tweet.key(),
+                author: tweet.author,
+                reports: tweet.reports,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn withdraw_report(ctx: Context<WithdrawReport>) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.reports.decrement()?;
+
+        Ok(())
+    }
+
+    pub fn moderate_tweet(ctx: Context<ModerateTweet>, hidden: bool) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.hidden = hidden;
+
+        Ok(())
+    }
+
+    /// Imports likes collected elsewhere as the merkle root of the users who liked the
+    /// tweet. The likes are only created when claimed with `claim_imported_like`, so the
+    /// admin does not pay for thousands of accounts at once.
+    pub fn import_likes_root(
+        ctx: Context<ImportLikesRoot>,
+        root: [u8; 32],
+        count: u64,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.imported_likes_root = root;
+        tweet.imported_likes_count = count;
+
+        Ok(())
+    }
+
+    /// Changes the topic of a tweet and nothing else, so it cannot clobber a concurrent edit
+    /// of its content. The TopicIndex accounts of the old and new topics can be passed as
+    /// remaining accounts, like with `update_tweet`.
+    pub fn set_topic<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetTopic<'info>>,
+        topic: String,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let author: &Signer = &ctx.accounts.author;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+
+        // Topics are stored lowercase, see `normalize_tweet`.
+        let topic = topic.to_lowercase();
+        if topic.chars().count() > config.max_topic_len as usize {
+            return Err(error!(ErrorCode::TopicTooLong));
+        }
+
+        move_topic_index(
+            tweet.key(),
+            &tweet.topic,
+            &topic,
+            &mut ctx.remaining_accounts.iter().peekable(),
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        let old_topic = std::mem::replace(&mut tweet.topic, topic);
+
+        emit!(TweetTopicChanged {
+            tweet: tweet.key(),
+            author: tweet.author,
+            old_topic,
+            new_topic: tweet.topic.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Adds the views counted off chain by the oracle to a tweet. Each tweet can only be
+    /// synced once per minute so the same batch of views cannot be pushed twice.
+    pub fn record_impressions(ctx: Context<RecordImpressions>, count: u32) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let clock: Clock = Clock::get()?;
+
+        if clock.unix_timestamp - tweet.last_impression_sync < IMPRESSION_SYNC_WINDOW {
+            return Err(error!(ErrorCode::SyncTooSoon));
+        }
+
+        tweet.views = tweet
+            .views
+            .checked_add(count as u64)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        tweet.last_impression_sync = clock.unix_timestamp;
+
+        Ok(())
+    }
+
+    /// Counts the signer as a viewer of a tweet. Each wallet pays the rent of its own
+    /// `View` so views stay costly to inflate, and viewing the same tweet again is a no-op.
+    pub fn record_view(ctx: Context<RecordView>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let view: &mut Account<View> = &mut ctx.accounts.view;
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let clock: Clock = Clock::get()?;
+
+        // `init_if_needed` hands back the existing view, which already has its viewer set.
+        if view.viewer != Pubkey::default() {
+            return Ok(());
+        }
+
+        view.tweet = tweet.key();
+        view.viewer = ctx.accounts.viewer.key();
+        view.viewed_at = clock.unix_timestamp;
+        view.bump = *ctx.bumps.get("view").unwrap();
+        tweet.view_count.increment()?;
+
+        Ok(())
+    }
+
+    /// Creates the like of `leaf_user` once its proof matches the imported root, the leaf
+    /// being the hash of their public key. Anyone can pay for the claim, the like belongs
+    /// to `leaf_user` who gets its rent back when unliking the tweet. The claim itself is
+    /// recorded in a LikeClaim that is never closed, so a like removed with `unlike_tweet`
+    /// cannot be claimed again with the same proof.
+    pub fn claim_imported_like(
+        ctx: Context<ClaimImportedLike>,
+        proof: Vec<[u8; 32]>,
+        leaf_user: Pubkey,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let like: &mut Account<Like> = &mut ctx.accounts.like;
+        let claim: &mut Account<LikeClaim> = &mut ctx.accounts.claim;
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        let leaf = hash(leaf_user.as_ref()).to_bytes();
+        if tweet.imported_likes_root == [0; 32]
+            || !verify_merkle_proof(&proof, &tweet.imported_likes_root, leaf)
+        {
+            return Err(error!(ErrorCode::InvalidProof));
+        }
+
+        like.tweet = tweet.key();
+        like.liker = leaf_user;
+        like.timestamp = Clock::get()?.unix_timestamp;
+        like.bump = *ctx.bumps.get("like").unwrap();
+
+        claim.tweet = tweet.key();
+        claim.liker = leaf_user;
+        claim.bump = *ctx.bumps.get("claim").unwrap();
+
+        tweet.like_count.increment()?;
+
+        emit!(TweetLiked {
+            tweet: tweet.key(),
+            liker: like.liker,
+            timestamp: like.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sends up to 5 tweets at once, one new keypair account per input passed through the
+    /// remaining accounts in the same order. Anchor's `init` cannot target remaining
+    /// accounts so each one is created here with the same `Tweet::LEN` space as
+    /// `send_tweet`, funded by the author with the rent-exempt minimum for that space.
+    /// The author's RecentPost, optional unless a cooldown is active, may come before them.
+    /// Every tweet goes through the content checks of a single tweet, but the batch counts
+    /// as one post for the cooldown, the minimum post interval and the duplicate window.
+    /// The batch is atomic, the first invalid entry reverts all of it and logs its index.
+    pub fn send_tweets_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendTweetsBatch<'info>>,
+        tweets: Vec<TweetInput>,
+    ) -> Result<()> {
+        let author: &Signer = &ctx.accounts.author;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+
+        if tweets.len() > MAX_BATCH_SIZE {
+            return Err(error!(ErrorCode::BatchTooLarge));
+        }
+
+        let mut remaining_accounts = ctx.remaining_accounts.iter().peekable();
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        let recent_info = remaining_accounts.next_if(|info| *info.key == recent_key);
+        let tweet_infos: Vec<&AccountInfo<'info>> = remaining_accounts.collect();
+        if tweets.len() != tweet_infos.len() {
+            return Err(error!(ErrorCode::BatchAccountMismatch));
+        }
+
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+
+        let rent_lamports = Rent::get()?.minimum_balance(Tweet::LEN);
+        for (index, (input, tweet_info)) in tweets.into_iter().zip(tweet_infos).enumerate() {
+            let log_index = |error| {
+                msg!("Invalid batch entry at index {}", index);
+                error
+            };
+            let (topic, content) =
+                validate_batch_entry(&input, tweet_info, config).map_err(log_index)?;
+
+            // Offset by the index so the tweets of a batch keep their order.
+            let timestamp = clock.unix_timestamp + index as i64;
+            let tweet = Tweet::new(*author.key, *author.key, topic, content, timestamp);
+            // The offsets are not real gaps between posts, so only the first tweet is checked
+            // against the author's previous post and the batch is timed from it.
+            if index == 0 {
+                ensure_can_post(
+                    &tweet,
+                    profile,
+                    recent_info,
+                    config,
+                    &author.to_account_info(),
+                    &ctx.accounts.system_program,
+                    ctx.program_id,
+                )
+                .map_err(log_index)?;
+            }
+            charge_post_fee(
+                config,
+                &author.to_account_info(),
+                &ctx.accounts.treasury.to_account_info(),
+                &ctx.accounts.system_program,
+            )?;
+
+            // The system program requires the new tweet account to sign, like with `init`.
+            invoke(
+                &system_instruction::create_account(
+                    author.key,
+                    tweet_info.key,
+                    rent_lamports,
+                    Tweet::LEN as u64,
+                    ctx.program_id,
+                ),
+                &[
+                    author.to_account_info(),
+                    tweet_info.clone(),
+                    ctx.accounts.system_program.clone(),
+                ],
+            )?;
+
+            // Serializing through Anchor also writes the account discriminator.
+            store_account(tweet_info, &tweet)?;
+
+            emit!(TweetSent {
+                tweet: *tweet_info.key,
+                author: tweet.author,
+                topic: tweet.topic,
+                timestamp: tweet.timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Closes up to 20 of the signer's tweets at once and refunds their rent to whoever
+    /// posted each of them. The first `tweet_count` remaining accounts are the tweets. They
+    /// are followed, in any order, by the parents of the replies that are not deleted in the
+    /// same batch, the wallets other than the signer that posted some of the tweets, and
+    /// optionally the signer's profile and the AuthorIndex pages holding the tweets. Each
+    /// tweet follows the rules and the cleanup of `delete_tweet`, so a reply should come
+    /// before its parent for the parent's reply count to reach zero first. The batch is
+    /// atomic, a single tweet that cannot be deleted reverts all of it and logs its index.
+    pub fn delete_tweets_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, DeleteTweetsBatch<'info>>,
+        tweet_count: u8,
+    ) -> Result<()> {
+        let author: &Signer = &ctx.accounts.author;
+        let tweet_count = tweet_count as usize;
+
+        if tweet_count > MAX_DELETE_BATCH_SIZE {
+            return Err(error!(ErrorCode::DeleteBatchTooLarge));
+        }
+
+        let tweets = ctx
+            .remaining_accounts
+            .get(..tweet_count)
+            .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+        let (profile_key, _) =
+            Pubkey::find_program_address(&[PROFILE_SEED, author.key.as_ref()], ctx.program_id);
+        let profile_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|info| *info.key == profile_key);
+        for (index, tweet_info) in tweets.iter().enumerate() {
+            delete_batch_entry(
+                tweet_info,
+                author,
+                profile_info,
+                ctx.remaining_accounts,
+                ctx.program_id,
+            )
+            .map_err(|error| {
+                msg!("Invalid batch entry at index {}", index);
+                error
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn close_revision(_ctx: Context<CloseRevision>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Creates a tweet that stays unpublished until `publish_tweet` is called after
+    /// `publish_at`. Likes and replies are refused until then.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn schedule_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, ScheduleTweet<'info>>,
+        topic: String,
+        content: String,
+        publish_at: i64,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, config)?;
+
+        if publish_at <= clock.unix_timestamp {
+            return Err(error!(ErrorCode::InvalidPublishTime));
+        }
+
+        **tweet = Tweet {
+            published: false,
+            publish_at: Some(publish_at),
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                topic,
+                content,
+                clock.unix_timestamp,
+            )
+        };
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        Ok(())
+    }
+
+    /// Sends a long-form tweet of up to 4000 chars. Timelines show its preview, the first 280
+    /// chars of the content, which is derived here so a client cannot show a misleading one.
+    /// Accounts created by an instruction are capped at 10240 bytes, so content made mostly
+    /// of multi-byte chars can run out of space before 4000 chars.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn send_long_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendLongTweet<'info>>,
+        topic: String,
+        content: String,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        if topic.chars().count() > config.max_topic_len as usize {
+            return Err(error!(ErrorCode::TopicTooLong));
+        }
+
+        if content.chars().count() > MAX_LONG_CONTENT_CHARS {
+            return Err(error!(ErrorCode::LongContentTooLong));
+        }
+
+        **tweet = Tweet {
+            preview: content[..preview_end(&content)].to_string(),
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                topic,
+                content,
+                clock.unix_timestamp,
+            )
+        };
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sends a tweet reserved to the holders of the gate mint set on the config. The
+    /// holding is checked by the account constraints.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn send_gated_tweet<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendGatedTweet<'info>>,
+        topic: String,
+        content: String,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, config)?;
+
+        **tweet = Tweet::new(
+            *author.key,
+            *author.key,
+            topic,
+            content,
+            clock.unix_timestamp,
+        );
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sends a tweet whose content is stored off chain, on IPFS or Arweave for instance.
+    /// Only its URI and the hash clients check the fetched content against are stored, so
+    /// the account rent is about half the rent of an inline tweet.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn send_tweet_ref<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendTweetRef<'info>>,
+        content_uri: String,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author: &Signer = &ctx.accounts.author;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        if content_uri.len() > MAX_CONTENT_URI_LENGTH {
+            return Err(error!(ErrorCode::ContentUriTooLong));
+        }
+
+        if content_hash == [0; 32] {
+            return Err(error!(ErrorCode::InvalidContentHash));
+        }
+
+        **tweet = Tweet {
+            content_hash,
+            storage_kind: STORAGE_REF,
+            content_uri,
+            ..Tweet::new(
+                *author.key,
+                *author.key,
+                String::new(),
+                String::new(),
+                clock.unix_timestamp,
+            )
+        };
+        fill_new_profile(
+            profile,
+            *author.key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        let (recent_key, _) = recent_post_address(author.key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+        charge_post_fee(
+            &ctx.accounts.config,
+            &author.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Posts a tweet relayed by a sponsor who pays its rent and fees, for authors without
+    /// SOL. The author signs the tweet off chain and the relayer verifies that signature with
+    /// an Ed25519 program instruction placed right before this one. The nonce of the author's
+    /// profile is part of what they sign so the same signed tweet cannot be replayed.
+    /// Remaining accounts are the author's RecentPost, optional unless a cooldown is active.
+    pub fn send_tweet_sponsored<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendTweetSponsored<'info>>,
+        topic: String,
+        content: String,
+        author_sig_nonce: u64,
+    ) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+        let author_key: Pubkey = ctx.accounts.author.key();
+        let relayer: &Signer = &ctx.accounts.relayer;
+        let config: &Account<Config> = &ctx.accounts.config;
+        let clock: Clock = Clock::get()?;
+
+        ensure_not_paused(config)?;
+
+        let (signer, message) = verified_ed25519_message(&ctx.accounts.instructions)?;
+        let expected_message = SponsoredTweetMessage {
+            program_id: *ctx.program_id,
+            nonce: author_sig_nonce,
+            topic: topic.clone(),
+            content: content.clone(),
+        }
+        .try_to_vec()?;
+        if signer != author_key || message != expected_message {
+            return Err(error!(ErrorCode::InvalidSignature));
+        }
+
+        fill_new_profile(
+            profile,
+            author_key,
+            clock.unix_timestamp,
+            *ctx.bumps.get("profile").unwrap(),
+        );
+        if author_sig_nonce != profile.nonce {
+            return Err(error!(ErrorCode::InvalidNonce));
+        }
+        profile.nonce.increment()?;
+
+        let (topic, content) = normalize_tweet(&topic, &content)?;
+        ensure_tweet_limits(&topic, &content, config)?;
+
+        // The relayer pays the posting fee along with the rent.
+        charge_post_fee(
+            config,
+            &relayer.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        // The relayer paid the rent, so it is who posted the tweet and gets the rent back.
+        **tweet = Tweet::new(
+            author_key,
+            *relayer.key,
+            topic,
+            content,
+            clock.unix_timestamp,
+        );
+        let (recent_key, _) = recent_post_address(&author_key, ctx.program_id);
+        ensure_can_post(
+            tweet,
+            profile,
+            ctx.remaining_accounts
+                .iter()
+                .find(|info| *info.key == recent_key),
+            config,
+            &relayer.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless crank publishing a scheduled tweet once its time has come.
+    pub fn publish_tweet(ctx: Context<PublishTweet>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let clock: Clock = Clock::get()?;
+
+        let publish_at = tweet.publish_at.ok_or(ErrorCode::TweetAlreadyPublished)?;
+        if clock.unix_timestamp < publish_at {
+            return Err(error!(ErrorCode::PublishTimeNotReached));
+        }
+
+        tweet.published = true;
+        tweet.timestamp = clock.unix_timestamp;
+
+        emit!(TweetSent {
+            tweet: tweet.key(),
+            author: tweet.author,
+            topic: tweet.topic.clone(),
+            timestamp: tweet.timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless crank revealing a time-locked tweet once its reveal time has come.
+    pub fn reveal_tweet(ctx: Context<RevealTweet>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let clock: Clock = Clock::get()?;
+
+        match tweet.scheduled_reveal {
+            Some(scheduled_reveal) if clock.unix_timestamp >= scheduled_reveal => {}
+            _ => return Err(error!(ErrorCode::NotYetRevealable)),
+        }
+
+        tweet.is_revealed = true;
+
+        emit!(TweetRevealed {
+            tweet: tweet.key(),
+            author: tweet.author,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_scheduled_tweet(_ctx: Context<CancelScheduledTweet>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Mutes are only read by clients to filter their feed, the program never enforces them.
+    pub fn mute_user(ctx: Context<MuteUser>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let mute: &mut Account<Mute> = &mut ctx.accounts.mute;
+        let clock: Clock = Clock::get()?;
+
+        mute.muter = ctx.accounts.muter.key();
+        mute.muted = ctx.accounts.muted.key();
+        mute.timestamp = clock.unix_timestamp;
+
+        Ok(())
+    }
+
+    pub fn unmute_user(_ctx: Context<UnmuteUser>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Permissionless crank closing an expired tweet. The cranker keeps 10% of the rent
+    /// as an incentive and whoever posted the tweet gets the rest back. Like `delete_tweet`,
+    /// a tweet with replies or comments cannot be closed, and an expired reply needs its
+    /// parent passed as the remaining account to decrement its reply count.
+    pub fn reap_expired_tweet(ctx: Context<ReapExpiredTweet>) -> Result<()> {
+        let tweet_info: AccountInfo = ctx.accounts.tweet.to_account_info();
+        let cranker_info: AccountInfo = ctx.accounts.cranker.to_account_info();
+        let clock: Clock = Clock::get()?;
+
+        if !ctx.accounts.tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetNotExpired));
+        }
+
+        if let Some(parent_key) = ctx.accounts.tweet.reply_to {
+            let parent_info = ctx
+                .remaining_accounts
+                .first()
+                .filter(|info| *info.key == parent_key)
+                .ok_or(ErrorCode::ParentTweetRequired)?;
+            let mut parent: Tweet = load_account(parent_info, ctx.program_id)?;
+            parent.reply_count.decrement()?;
+            store_account(parent_info, &parent)?;
+        }
+
+        // The remaining 90% go to whoever posted the tweet when the account is closed.
+        let cranker_share = tweet_info.lamports() / 10;
+        **tweet_info.try_borrow_mut_lamports()? -= cranker_share;
+        **cranker_info.try_borrow_mut_lamports()? += cranker_share;
+
+        emit!(TweetDeleted {
+            tweet: tweet_info.key(),
+            author: ctx.accounts.tweet.author,
+        });
+
+        Ok(())
+    }
+
+    /// Grows a tweet account so it can hold up to `new_content_len` chars of content once
+    /// the configured limit has been raised. Whoever posted the tweet tops up the rent for
+    /// the new size, so they sign along with the author when it was a delegate.
+    pub fn realloc_tweet(ctx: Context<ReallocTweet>, new_content_len: u16) -> Result<()> {
+        let tweet_info: AccountInfo = ctx.accounts.tweet.to_account_info();
+        let config: &Account<Config> = &ctx.accounts.config;
+
+        ensure_not_paused(config)?;
+
+        if ctx.accounts.tweet.version < TWEET_VERSION {
+            return Err(error!(ErrorCode::AccountVersionTooOld));
+        }
+
+        if new_content_len > config.max_content_len {
+            return Err(error!(ErrorCode::ContentTooLong));
+        }
+
+        // Only the content grows, whatever the account already holds for media, a preview or
+        // a previous realloc is kept.
+        let extra_space = (new_content_len as usize * 4).saturating_sub(MAX_CONTENT_LENGTH);
+        let current_extra_space = ctx
+            .accounts
+            .tweet
+            .extra_content_space(tweet_info.data_len());
+        if extra_space < current_extra_space {
+            return Err(error!(ErrorCode::TweetCannotShrink));
+        }
+        let new_space = tweet_info.data_len() + extra_space - current_extra_space;
+
+        grow_tweet(
+            &tweet_info,
+            &ctx.accounts.posted_by.to_account_info(),
+            &ctx.accounts.system_program,
+            new_space,
+        )
+    }
+
+    /// Upgrades a tweet to the current layout. Tweets created before accounts were
+    /// versioned have no version byte, which reads as 0, and no reserved space so they are
+    /// grown first. Version 1 tweets predate time-locked reveals so they are all revealed.
+    /// Newer tweets are grown to make room for the token gate and who posted them. The
+    /// growth is paid by whoever posted the tweet, the author for tweets that predate
+    /// delegates.
+    pub fn migrate_tweet(ctx: Context<MigrateTweet>) -> Result<()> {
+        let tweet_info: AccountInfo = ctx.accounts.tweet.to_account_info();
+        let author: AccountInfo = ctx.accounts.author.to_account_info();
+
+        // An old tweet with long content can be too short to deserialize with the current
+        // layout, so it is checked by hand and grown by what the oldest version misses
+        // first, with the author fronting the rent. Once its version is known it goes back
+        // to its old size, which refunds the author, and grows by what its version misses.
+        if tweet_info.owner != ctx.program_id {
+            return Err(error!(ErrorCode::AccountNotOwnedByProgram));
+        }
+        if !tweet_info
+            .try_borrow_data()?
+            .starts_with(&Tweet::discriminator())
+        {
+            return Err(error!(
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            ));
+        }
+        let old_space = tweet_info.data_len();
+        grow_account(
+            &tweet_info,
+            &author,
+            &ctx.accounts.system_program,
+            old_space + Tweet::migration_space(0),
+        )?;
+
+        let mut tweet: Tweet = load_account(&tweet_info, ctx.program_id)?;
+        let version = tweet.version;
+        if tweet.author != *author.key {
+            return Err(error!(ErrorCode::NotAuthor));
+        }
+
+        if version >= TWEET_VERSION {
+            return Err(error!(ErrorCode::AccountAlreadyMigrated));
+        }
+
+        let posted_by = if version < 4 {
+            tweet.author
+        } else {
+            tweet.posted_by
+        };
+        let posted_by_info: AccountInfo = ctx.accounts.posted_by.to_account_info();
+        if *posted_by_info.key != posted_by {
+            return Err(error!(ErrorCode::PostedByMismatch));
+        }
+
+        shrink_account(&tweet_info, &author, old_space)?;
+        grow_tweet(
+            &tweet_info,
+            &posted_by_info,
+            &ctx.accounts.system_program,
+            old_space + Tweet::migration_space(version),
+        )?;
+
+        if version < 2 {
+            tweet.scheduled_reveal = None;
+            tweet.is_revealed = true;
+        }
+        if version < 3 {
+            tweet.gate_mint = None;
+            tweet.gate_min_amount = 0;
+        }
+        if version < 4 {
+            tweet.posted_by = tweet.author;
+        }
+        if version < 5 {
+            tweet.storage_kind = STORAGE_INLINE;
+            tweet.content_uri = String::new();
+        }
+        if version < 6 {
+            tweet.reaction_counts = [0; REACTION_KIND_COUNT];
+        }
+        if version < 7 {
+            tweet.imported_likes_root = [0; 32];
+            tweet.imported_likes_count = 0;
+        }
+        if version < 8 {
+            tweet.views = 0;
+            tweet.last_impression_sync = 0;
+        }
+        if version < 9 {
+            tweet.view_count = 0;
+        }
+        if version < 10 {
+            tweet.space = None;
+        }
+        if version < 11 {
+            tweet.co_authors = Vec::new();
+        }
+        tweet.version = TWEET_VERSION;
+
+        store_account(&tweet_info, &tweet)
+    }
+
+    /// Sends an end-to-end encrypted message. The program never sees the plaintext, it only
+    /// stores the ciphertext and keeps count of the messages in the conversation.
+    pub fn send_dm(ctx: Context<SendDm>, recipient: Pubkey, ciphertext: Vec<u8>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let message: &mut Account<Message> = &mut ctx.accounts.message;
+        let conversation: &mut Account<Conversation> = &mut ctx.accounts.conversation;
+        let sender: &Signer = &ctx.accounts.sender;
+        let clock: Clock = Clock::get()?;
+
+        if recipient == *sender.key {
+            return Err(error!(ErrorCode::CannotMessageSelf));
+        }
+
+        if ciphertext.len() > MAX_CIPHERTEXT_LENGTH {
+            return Err(error!(ErrorCode::CiphertextTooLong));
+        }
+
+        if conversation.message_count == 0 {
+            conversation.user_a = sender.key().min(recipient);
+            conversation.user_b = sender.key().max(recipient);
+            conversation.bump = *ctx.bumps.get("conversation").unwrap();
+        }
+        conversation.message_count.increment()?;
+        conversation.last_message_at = clock.unix_timestamp;
+
+        message.sender = *sender.key;
+        message.recipient = recipient;
+        message.conversation = conversation.key();
+        message.timestamp = clock.unix_timestamp;
+        message.ciphertext = ciphertext;
+
+        Ok(())
+    }
+
+    pub fn delete_dm(ctx: Context<DeleteDm>) -> Result<()> {
+        let conversation: &mut Account<Conversation> = &mut ctx.accounts.conversation;
+
+        conversation.message_count.decrement()?;
+
+        Ok(())
+    }
+
+    /// Lets `delegate` post, edit and delete tweets on behalf of the signer. Delegates cannot
+    /// manage other delegates or the profile, which both require the owner to sign.
+    pub fn add_delegate(ctx: Context<AddDelegate>, delegate: Pubkey) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let delegation: &mut Account<Delegate> = &mut ctx.accounts.delegation;
+        let owner: &Signer = &ctx.accounts.owner;
+        let clock: Clock = Clock::get()?;
+
+        if delegate == *owner.key {
+            return Err(error!(ErrorCode::CannotDelegateToSelf));
+        }
+
+        delegation.owner = *owner.key;
+        delegation.delegate = delegate;
+        delegation.timestamp = clock.unix_timestamp;
+        delegation.bump = *ctx.bumps.get("delegation").unwrap();
+
+        Ok(())
+    }
+
+    pub fn remove_delegate(_ctx: Context<RemoveDelegate>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Mints a tweet as a supply-1 NFT held by its author, described by the off-chain JSON at
+    /// `uri`. The metadata is immutable and the master edition takes over the mint authority
+    /// with a max supply of 0, so nobody can ever mint a second token or print copies. The
+    /// mint has no freeze authority, the author's token can never be frozen.
+    pub fn mint_tweet_nft(ctx: Context<MintTweetNft>, uri: String) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        if uri.len() > MAX_NFT_URI_LENGTH {
+            return Err(error!(ErrorCode::NftUriTooLong));
+        }
+
+        let bump = *ctx.bumps.get("mint_authority").unwrap();
+        let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, &[bump]];
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        invoke_signed(
+            &create_metadata_accounts_v2(
+                mpl_token_metadata::ID,
+                ctx.accounts.metadata.key(),
+                ctx.accounts.mint.key(),
+                ctx.accounts.mint_authority.key(),
+                ctx.accounts.author.key(),
+                ctx.accounts.author.key(),
+                nft_name(&ctx.accounts.tweet.content),
+                NFT_SYMBOL.to_string(),
+                uri,
+                None,
+                0,
+                true,  // The update authority signs.
+                false, // Immutable, the metadata can never be changed.
+                None,
+                None,
+            ),
+            &[
+                ctx.accounts.metadata.clone(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.mint_authority.clone(),
+                ctx.accounts.author.to_account_info(),
+                ctx.accounts.system_program.clone(),
+                ctx.accounts.rent.to_account_info(),
+                ctx.accounts.token_metadata_program.clone(),
+            ],
+            &[seeds],
+        )?;
+
+        invoke_signed(
+            &create_master_edition_v3(
+                mpl_token_metadata::ID,
+                ctx.accounts.master_edition.key(),
+                ctx.accounts.mint.key(),
+                ctx.accounts.author.key(),
+                ctx.accounts.mint_authority.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.author.key(),
+                Some(0),
+            ),
+            &[
+                ctx.accounts.master_edition.clone(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.author.to_account_info(),
+                ctx.accounts.mint_authority.clone(),
+                ctx.accounts.metadata.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.clone(),
+                ctx.accounts.rent.to_account_info(),
+                ctx.accounts.token_metadata_program.clone(),
+            ],
+            &[seeds],
+        )?;
+
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        tweet.nft_mint = Some(ctx.accounts.mint.key());
+
+        Ok(())
+    }
+
+    /// Sets the monthly price of a subscription to the profile, 0 closes new subscriptions.
+    pub fn set_subscription_price(ctx: Context<UpdateProfile>, price: u64) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        ctx.accounts.profile.subscription_price = price;
+
+        Ok(())
+    }
+
+    /// Subscribes to a creator for `months` months, paid upfront at the creator's price.
+    /// Clients show the creator's subscriber-only content while `expires_at` is ahead.
+    pub fn subscribe(ctx: Context<Subscribe>, months: u8) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let subscription: &mut Account<Subscription> = &mut ctx.accounts.subscription;
+        let subscriber: &Signer = &ctx.accounts.subscriber;
+        let creator: &SystemAccount = &ctx.accounts.creator;
+        let clock: Clock = Clock::get()?;
+
+        if creator.key() == *subscriber.key {
+            return Err(error!(ErrorCode::CannotSubscribeToSelf));
+        }
+
+        let price = ctx.accounts.creator_profile.subscription_price;
+        pay_subscription(
+            &subscriber.to_account_info(),
+            &creator.to_account_info(),
+            &ctx.accounts.system_program,
+            price,
+            months,
+        )?;
+
+        subscription.creator = creator.key();
+        subscription.subscriber = *subscriber.key;
+        subscription.price = price;
+        subscription.expires_at = clock.unix_timestamp + months as i64 * SECONDS_PER_MONTH;
+        subscription.bump = *ctx.bumps.get("subscription").unwrap();
+
+        Ok(())
+    }
+
+    /// Extends a subscription at the creator's current price. Active subscriptions are
+    /// extended from their expiry, lapsed ones from now.
+    pub fn renew_subscription(ctx: Context<RenewSubscription>, months: u8) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let subscription: &mut Account<Subscription> = &mut ctx.accounts.subscription;
+        let clock: Clock = Clock::get()?;
+
+        let price = ctx.accounts.creator_profile.subscription_price;
+        pay_subscription(
+            &ctx.accounts.subscriber.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.system_program,
+            price,
+            months,
+        )?;
+
+        let start = subscription.expires_at.max(clock.unix_timestamp);
+        subscription.price = price;
+        subscription.expires_at = start + months as i64 * SECONDS_PER_MONTH;
+
+        Ok(())
+    }
+
+    /// Reacts to a tweet, or switches the kind of an existing reaction, moving its count
+    /// from the old kind to the new one. Reactions are counted apart from likes.
+    pub fn react_to_tweet(ctx: Context<ReactToTweet>, kind: ReactionKind) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let reaction: &mut Account<Reaction> = &mut ctx.accounts.reaction;
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+        let user: &Signer = &ctx.accounts.user;
+        let clock: Clock = Clock::get()?;
+
+        if tweet.is_expired(&clock) {
+            return Err(error!(ErrorCode::TweetExpired));
+        }
+
+        // A new reaction has not been written yet so it still points to no tweet.
+        if reaction.tweet != Pubkey::default() {
+            tweet.reaction_counts[reaction.kind as usize].decrement()?;
+        }
+        tweet.reaction_counts[kind as usize].increment()?;
+
+        reaction.tweet = tweet.key();
+        reaction.user = *user.key;
+        reaction.kind = kind;
+        reaction.timestamp = clock.unix_timestamp;
+        reaction.bump = *ctx.bumps.get("reaction").unwrap();
+
+        Ok(())
+    }
+
+    pub fn remove_reaction(ctx: Context<RemoveReaction>) -> Result<()> {
+        let tweet: &mut Account<Tweet> = &mut ctx.accounts.tweet;
+
+        tweet.reaction_counts[ctx.accounts.reaction.kind as usize].decrement()?;
+
+        Ok(())
+    }
+
+    /// Creates a list of accounts at the next index of its owner's profile, so each owner
+    /// can create up to 255 lists, counting deleted ones.
+    pub fn create_list(ctx: Context<CreateList>, name: String) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let list: &mut Account<List> = &mut ctx.accounts.list;
+        let profile: &mut Account<UserProfile> = &mut ctx.accounts.profile;
+
+        if name.chars().count() > 32 {
+            return Err(error!(ErrorCode::ListNameTooLong));
+        }
+
+        list.owner = ctx.accounts.owner.key();
+        list.index = profile.list_count;
+        list.name = name;
+        list.member_count = 0;
+        list.bump = *ctx.bumps.get("list").unwrap();
+
+        profile.list_count.increment()?;
+
+        Ok(())
+    }
+
+    pub fn add_list_member(ctx: Context<AddListMember>, member: Pubkey) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let list: &mut Account<List> = &mut ctx.accounts.list;
+        let list_member: &mut Account<ListMember> = &mut ctx.accounts.list_member;
+
+        if list.member_count >= MAX_LIST_MEMBERS {
+            return Err(error!(ErrorCode::ListFull));
+        }
+
+        list_member.list = list.key();
+        list_member.member = member;
+        list_member.added_at = Clock::get()?.unix_timestamp;
+        list_member.bump = *ctx.bumps.get("list_member").unwrap();
+
+        list.member_count.increment()?;
+
+        Ok(())
+    }
+
+    pub fn remove_list_member(ctx: Context<RemoveListMember>) -> Result<()> {
+        ctx.accounts.list.member_count.decrement()?;
+
+        Ok(())
+    }
+
+    /// Closes an empty list, its members have to be removed first so none of their
+    /// accounts is left behind.
+    pub fn delete_list(_ctx: Context<DeleteList>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Creates a curated list of tweets. Unlike lists of accounts, tweet lists are not
+    /// derived from the owner so they can be created without a profile.
+    pub fn create_tweet_list(
+        ctx: Context<CreateTweetList>,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let list: &mut Account<TweetList> = &mut ctx.accounts.list;
+
+        if name.chars().count() > 50 {
+            return Err(error!(ErrorCode::TweetListNameTooLong));
+        }
+
+        if description.chars().count() > 200 {
+            return Err(error!(ErrorCode::TweetListDescriptionTooLong));
+        }
+
+        list.owner = ctx.accounts.owner.key();
+        list.name = name;
+        list.description = description;
+        list.item_count = 0;
+
+        Ok(())
+    }
+
+    /// Adds a tweet to a list, a tweet can only be in each list once.
+    pub fn add_to_list(ctx: Context<AddToList>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let list: &mut Account<TweetList> = &mut ctx.accounts.list;
+        let list_item: &mut Account<ListItem> = &mut ctx.accounts.list_item;
+
+        list_item.list = list.key();
+        list_item.tweet = ctx.accounts.tweet.key();
+        list_item.added_at = Clock::get()?.unix_timestamp;
+        list_item.bump = *ctx.bumps.get("list_item").unwrap();
+
+        list.item_count.increment()?;
+
+        Ok(())
+    }
+
+    pub fn remove_from_list(ctx: Context<RemoveFromList>) -> Result<()> {
+        ctx.accounts.list.item_count.decrement()?;
+
+        Ok(())
+    }
+
+    /// Creates a community space, derived from the hash of its name so each name is only
+    /// taken once. The owner joins it like anyone else before posting into it.
+    pub fn create_space(
+        ctx: Context<CreateSpace>,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let space: &mut Account<Space> = &mut ctx.accounts.space;
+
+        if name.chars().count() > 50 {
+            return Err(error!(ErrorCode::SpaceNameTooLong));
+        }
+
+        if description.chars().count() > 200 {
+            return Err(error!(ErrorCode::SpaceDescriptionTooLong));
+        }
+
+        space.owner = ctx.accounts.owner.key();
+        space.name = name;
+        space.description = description;
+        space.member_count = 0;
+        space.tweet_count = 0;
+        space.bump = *ctx.bumps.get("space").unwrap();
+
+        Ok(())
+    }
+
+    pub fn join_space(ctx: Context<JoinSpace>) -> Result<()> {
+        ensure_not_paused(&ctx.accounts.config)?;
+
+        let member: &mut Account<SpaceMember> = &mut ctx.accounts.member;
+
+        member.space = ctx.accounts.joined_space.key();
+        member.user = ctx.accounts.user.key();
+        member.joined_at = Clock::get()?.unix_timestamp;
+        member.bump = *ctx.bumps.get("member").unwrap();
+
+        ctx.accounts.joined_space.member_count.increment()?;
+
+        Ok(())
+    }
+
+    /// Lets the owner of a space remove one of its members, who gets the rent of their
+    /// membership back. Their tweets stay in the space but they cannot post new ones.
+    pub fn remove_member(ctx: Context<RemoveMember>) -> Result<()> {
+        ctx.accounts.space.member_count.decrement()?;
+
+        Ok(())
+    }
+
+    pub fn transfer_space_ownership(
+        ctx: Context<TransferSpaceOwnership>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.space.owner = new_owner;
+
+        Ok(())
+    }
+}
+
+/// Hashes the leaf up with each sibling of the proof. Every pair is sorted before being
+/// hashed so proofs do not need to tell on which side the siblings are.
+fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+
+    computed == *root
+}
+
+/// Closes one tweet of `delete_tweets_batch` once it passed the checks of `DeleteTweet`,
+/// with the same cleanup as `delete_tweet`. The accounts it needs are looked up by key since
+/// the batch cannot order them per tweet.
+fn delete_batch_entry<'info>(
+    tweet_info: &AccountInfo<'info>,
+    author: &Signer<'info>,
+    profile_info: Option<&AccountInfo<'info>>,
+    remaining_accounts: &[AccountInfo<'info>],
+    program_id: &Pubkey,
+) -> Result<()> {
+    // Checks the owner and the discriminator, so closed or foreign accounts are refused.
+    let tweet: Account<Tweet> = Account::try_from(tweet_info)?;
+
+    if tweet.author != *author.key {
+        return Err(error!(ErrorCode::NotAuthor));
+    }
+
+    if tweet.comments != 0 {
+        return Err(error!(ErrorCode::TweetHasComments));
+    }
+
+    if tweet.reply_count != 0 {
+        return Err(error!(ErrorCode::TweetHasReplies));
+    }
+
+    // The parent is required, it either is a later tweet of the batch or follows the tweets.
+    // A parent deleted in the same batch comes later, so its reply count is still live.
+    if let Some(parent_key) = tweet.reply_to {
+        let parent_info = remaining_accounts
+            .iter()
+            .find(|info| *info.key == parent_key)
+            .ok_or(ErrorCode::ParentTweetRequired)?;
+        release_parent(parent_info, program_id)?;
+    }
+
+    if let Some(profile_info) = profile_info {
+        clear_pin(&tweet.key(), profile_info, program_id)?;
+    }
+
+    // Only a page of the author that holds the tweet is tombstoned, others are skipped.
+    let index_info = remaining_accounts.iter().find(|info| {
+        load_account::<AuthorIndex>(info, program_id).map_or(false, |index| {
+            index.author == tweet.author && index.tweets.contains(&tweet.key())
+        })
+    });
+    if let Some(index_info) = index_info {
+        tombstone_index_slot(&tweet, index_info, program_id)?;
+    }
+
+    emit!(TweetDeleted {
+        tweet: tweet.key(),
+        author: tweet.author,
+    });
+
+    // Like `delete_tweet`, the rent goes back to whoever posted the tweet.
+    if tweet.posted_by == *author.key {
+        return tweet.close(author.to_account_info());
+    }
+    let posted_by_info = remaining_accounts
+        .iter()
+        .find(|info| *info.key == tweet.posted_by)
+        .ok_or(ErrorCode::PostedByMismatch)?;
+    tweet.close(posted_by_info.clone())
+}
+
+/// Decrements the reply count of the parent of a deleted reply.
+fn release_parent(parent_info: &AccountInfo, program_id: &Pubkey) -> Result<()> {
+    let mut parent: Tweet = load_account(parent_info, program_id)?;
+    parent.reply_count.decrement()?;
+    store_account(parent_info, &parent)
+}
+
+/// Unpins a deleted tweet from its author's profile, if it is the pinned one.
+fn clear_pin(tweet_key: &Pubkey, profile_info: &AccountInfo, program_id: &Pubkey) -> Result<()> {
+    let mut profile: UserProfile = load_account(profile_info, program_id)?;
+    if profile.pinned_tweet == Some(*tweet_key) {
+        profile.pinned_tweet = None;
+        store_account(profile_info, &profile)?;
+    }
+
+    Ok(())
+}
+
+/// Tombstones the slot of a deleted tweet in the AuthorIndex page holding it. Slots are never
+/// shifted so the position of the other tweets stays stable.
+fn tombstone_index_slot(
+    tweet: &Account<Tweet>,
+    index_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let mut index: AuthorIndex = load_account(index_info, program_id)?;
+    if index.author != tweet.author {
+        return Err(error!(ErrorCode::IndexPageMismatch));
+    }
+    let slot = index
+        .tweets
+        .iter()
+        .position(|key| *key == tweet.key())
+        .ok_or(ErrorCode::IndexPageMismatch)?;
+    index.tweets[slot] = Pubkey::default();
+    store_account(index_info, &index)
+}
+
+/// Deserializes a program account that was passed through the remaining accounts.
+fn load_account<T: AccountDeserialize>(info: &AccountInfo, program_id: &Pubkey) -> Result<T> {
+    if info.owner != program_id {
+        return Err(error!(ErrorCode::AccountNotOwnedByProgram));
+    }
+
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Resizes a program account, topping up its rent from the payer first. Anchor 0.22 has
+/// no `realloc` constraint so accounts are resized by hand.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_space: usize,
+) -> Result<()> {
+    let missing_lamports = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(new_space, false)?;
+
+    Ok(())
+}
+
+/// Grows a tweet with the rent paid by whoever posted it, who gets it all back when the tweet
+/// shrinks or closes. They have to sign even when the author is the signer.
+fn grow_tweet<'info>(
+    tweet: &AccountInfo<'info>,
+    posted_by: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_space: usize,
+) -> Result<()> {
+    if !posted_by.is_signer {
+        return Err(error!(ErrorCode::PostedByMustSign));
+    }
+
+    grow_account(tweet, posted_by, system_program, new_space)
+}
+
+/// Shrinks a program account and sends the rent it no longer needs to the recipient.
+fn shrink_account<'info>(
+    account: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    new_space: usize,
+) -> Result<()> {
+    account.realloc(new_space, false)?;
+
+    let excess_lamports = account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(new_space));
+    **account.try_borrow_mut_lamports()? -= excess_lamports;
+    **recipient.try_borrow_mut_lamports()? += excess_lamports;
+
+    Ok(())
+}
+
+/// Checked `+= 1` and `-= 1` so that every counter fails with the same errors.
+trait Counter {
+    fn increment(&mut self) -> Result<()>;
+    fn decrement(&mut self) -> Result<()>;
+}
+
+macro_rules! impl_counter {
+    ($($t:ty),*) => {
+        $(impl Counter for $t {
+            fn increment(&mut self) -> Result<()> {
+                *self = self.checked_add(1).ok_or(ErrorCode::CountOverflow)?;
+                Ok(())
+            }
+
+            fn decrement(&mut self) -> Result<()> {
+                *self = self.checked_sub(1).ok_or(ErrorCode::CounterUnderflow)?;
+                Ok(())
+            }
+        })*
+    };
+}
+
+impl_counter!(u8, u16, u32, u64);
+
+/// Serializes a program account, including its discriminator, back into its data.
+fn store_account<T: AccountSerialize>(info: &AccountInfo, account: &T) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    account.try_serialize(&mut writer)
+}
+
+/// Allocates a program-owned PDA that cannot go through Anchor's `init` constraint.
+/// Accounts that were pre-funded by someone else are topped up instead of created.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_lamports = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent_lamports,
+                space as u64,
+                &crate::ID,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+        return Ok(());
+    }
+
+    let missing_lamports = rent_lamports.saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, &crate::ID),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Rejects write instructions while the admin has paused the program. Cleanup instructions
+/// skip this check so users can still close their accounts during an emergency stop, as do
+/// the admin's and the oracle's, reports, and `migrate_tweet` which only upgrades a layout.
+fn ensure_not_paused(config: &Config) -> Result<()> {
+    if config.paused {
+        return Err(error!(ErrorCode::ProgramPaused));
+    }
+
+    Ok(())
+}
+
+/// Byte length of the first 280 chars of the content. Cutting where `char_indices` starts
+/// the next char never splits a multi-byte char.
+fn preview_end(content: &str) -> usize {
+    content
+        .char_indices()
+        .nth(MAX_PREVIEW_CHARS)
+        .map_or(content.len(), |(end, _)| end)
+}
+
+/// Names an NFT after the first 32 chars of its tweet, stopping earlier when multi-byte
+/// chars would exceed the 32 bytes allowed by the metadata program.
+fn nft_name(content: &str) -> String {
+    let mut name = String::new();
+    for c in content.chars().take(MAX_NFT_NAME_LENGTH) {
+        if name.len() + c.len_utf8() > MAX_NFT_NAME_LENGTH {
+            break;
+        }
+        name.push(c);
+    }
+
+    name
+}
+
+/// Loads the profile passed through the remaining accounts, creating a default one when the
+/// user has none yet so their first action does not need a separate `create_profile`. Only
+/// an empty account is initialized, an existing profile is never reset.
+fn load_or_create_profile<'info>(
+    profile_info: &AccountInfo<'info>,
+    authority: &Pubkey,
+    bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<UserProfile> {
+    if !profile_info.data_is_empty() {
+        return load_account(profile_info, program_id);
+    }
+
+    create_pda_account(
+        payer,
+        profile_info,
+        system_program,
+        UserProfile::LEN,
+        &[PROFILE_SEED, authority.as_ref(), &[bump]],
+    )?;
+    let profile = UserProfile::new(*authority, Clock::get()?.unix_timestamp, bump);
+    store_account(profile_info, &profile)?;
+
+    Ok(profile)
+}
+
+/// `init_if_needed` hands back an empty profile when the user had none, this turns it into
+/// their default profile.
+fn fill_new_profile(profile: &mut UserProfile, authority: Pubkey, created_at: i64, bump: u8) {
+    if profile.authority == Pubkey::default() {
+        *profile = UserProfile::new(authority, created_at, bump);
+    }
+}
+
+fn ensure_profile_active(profile: &UserProfile) -> Result<()> {
+    if !profile.active {
+        return Err(error!(ErrorCode::ProfileDeactivated));
+    }
+
+    Ok(())
+}
+
+/// Blocks cannot be enumerated on chain so the client supplies the candidate Block PDA of
+/// the author and the interacting user as an optimistic proof. We only trust it once its
+/// address matches those seeds and it is an initialized Block account owned by us.
+fn ensure_not_blocked(
+    author: &Pubkey,
+    user: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (block_key, _) =
+        Pubkey::find_program_address(&[BLOCK_SEED, author.as_ref(), user.as_ref()], program_id);
+    if let Some(block_info) = remaining_accounts
+        .iter()
+        .find(|info| *info.key == block_key)
+    {
+        if !block_info.data_is_empty() {
+            load_account::<Block>(block_info, program_id)?;
+            return Err(error!(ErrorCode::BlockedByAuthor));
+        }
+    }
+
+    Ok(())
+}
+
+/// Bumps the GlobalStats and today's DailyStats when they were passed through the remaining
+/// accounts. Skipping them is allowed so a congested stats account never blocks posting.
+/// The first instruction of the day creates its bucket.
+fn record_stat<'info>(
+    stat: Stat,
+    remaining_accounts: &[AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    clock: &Clock,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (stats_key, _) = Pubkey::find_program_address(&[STATS_SEED], program_id);
+    if let Some(stats_info) = remaining_accounts
+        .iter()
+        .find(|info| *info.key == stats_key)
+    {
+        let mut stats: GlobalStats = load_account(stats_info, program_id)?;
+        match stat {
+            Stat::Tweet => stats.total_tweets.increment()?,
+            Stat::Like => stats.total_likes.increment()?,
+            Stat::User => stats.total_users.increment()?,
+        }
+        store_account(stats_info, &stats)?;
+    }
+
+    let day = (clock.unix_timestamp / SECONDS_PER_DAY) as u32;
+    let day_bytes = day.to_le_bytes();
+    let (daily_key, bump) = Pubkey::find_program_address(&[STATS_SEED, &day_bytes], program_id);
+    if let Some(daily_info) = remaining_accounts
+        .iter()
+        .find(|info| *info.key == daily_key)
+    {
+        let mut daily: DailyStats = if daily_info.data_is_empty() {
+            create_pda_account(
+                payer,
+                daily_info,
+                system_program,
+                DailyStats::LEN,
+                &[STATS_SEED, &day_bytes, &[bump]],
+            )?;
+            DailyStats {
+                day,
+                tweets: 0,
+                likes: 0,
+                users: 0,
+                bump,
+                version: 0,
+            }
+        } else {
+            load_account(daily_info, program_id)?
+        };
+        match stat {
+            Stat::Tweet => daily.tweets.increment()?,
+            Stat::Like => daily.likes.increment()?,
+            Stat::User => daily.users.increment()?,
+        }
+        store_account(daily_info, &daily)?;
+    }
+
+    Ok(())
+}
+
+/// Shows a handle on the profile of its owner, or removes it, when that profile was passed
+/// through the remaining accounts.
+fn link_profile_handle(
+    owner: &Pubkey,
+    handle: &str,
+    linked: bool,
+    remaining_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (profile_key, _) =
+        Pubkey::find_program_address(&[PROFILE_SEED, owner.as_ref()], program_id);
+    if let Some(profile_info) = remaining_accounts
+        .iter()
+        .find(|info| *info.key == profile_key)
+    {
+        let mut profile: UserProfile = load_account(profile_info, program_id)?;
+        if linked {
+            profile.handle = Some(handle.to_string());
+        } else if profile.handle.as_deref() == Some(handle) {
+            profile.handle = None;
+        }
+        store_account(profile_info, &profile)?;
+    }
+
+    Ok(())
+}
+
+/// Transfers `months` months of a subscription from the subscriber to the creator.
+fn pay_subscription<'info>(
+    subscriber: &AccountInfo<'info>,
+    creator: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    price: u64,
+    months: u8,
+) -> Result<()> {
+    if months == 0 || months > MAX_SUBSCRIPTION_MONTHS {
+        return Err(error!(ErrorCode::InvalidSubscriptionMonths));
+    }
+
+    // A price of zero means the creator does not offer subscriptions.
+    if price == 0 {
+        return Err(error!(ErrorCode::SubscriptionsClosed));
+    }
+
+    let lamports = price
+        .checked_mul(months as u64)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    invoke(
+        &system_instruction::transfer(subscriber.key, creator.key, lamports),
+        &[subscriber.clone(), creator.clone(), system_program.clone()],
+    )?;
+
+    Ok(())
+}
+
+/// Checks a new tweet against the limits of the config. They count characters so multi-byte
+/// ones like emoji are not penalized, while the byte length still has to fit the account.
+fn ensure_tweet_limits(topic: &str, content: &str, config: &Config) -> Result<()> {
+    if topic.chars().count() > config.max_topic_len as usize {
+        return Err(error!(ErrorCode::TopicTooLong));
+    }
+
+    if content.chars().count() > config.max_content_len as usize
+        || content.len() > MAX_CONTENT_LENGTH
+    {
+        return Err(error!(ErrorCode::ContentTooLong));
+    }
+
+    Ok(())
+}
+
+/// Lowercases the topic so topic filters match whatever case the author typed, and trims
+/// the content so it cannot be padded with whitespace or made of whitespace only.
+fn normalize_tweet(topic: &str, content: &str) -> Result<(String, String)> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(error!(ErrorCode::ContentEmpty));
+    }
+
+    Ok((topic.to_lowercase(), content.to_string()))
+}
+
+fn validate_co_authors(co_authors: &[CoAuthor], author: &Pubkey) -> Result<()> {
+    if co_authors.len() > MAX_CO_AUTHORS {
+        return Err(error!(ErrorCode::TooManyCoAuthors));
+    }
+
+    if co_authors.iter().enumerate().any(|(i, co_author)| {
+        co_authors[..i]
+            .iter()
+            .any(|other| other.key == co_author.key)
+    }) {
+        return Err(error!(ErrorCode::DuplicateCoAuthor));
+    }
+
+    if co_authors.iter().any(|co_author| co_author.key == *author) {
+        return Err(error!(ErrorCode::AuthorIsCoAuthor));
+    }
+
+    // A share of zero would never be paid, the co-author would only be listed.
+    if co_authors.iter().any(|co_author| co_author.bps == 0) {
+        return Err(error!(ErrorCode::ZeroCoAuthorShare));
+    }
+
+    let total_bps: u64 = co_authors
+        .iter()
+        .map(|co_author| co_author.bps as u64)
+        .sum();
+    if total_bps > MAX_BPS {
+        return Err(error!(ErrorCode::CoAuthorSharesTooHigh));
+    }
+
+    Ok(())
+}
+
+fn validate_media(media: &[MediaAttachment]) -> Result<()> {
+    if media.len() > MAX_MEDIA_ATTACHMENTS {
+        return Err(error!(ErrorCode::TooManyAttachments));
+    }
+
+    if media
+        .iter()
+        .any(|attachment| attachment.uri.chars().count() > MAX_MEDIA_URI_CHARS)
+    {
+        return Err(error!(ErrorCode::MediaUriTooLong));
+    }
+
+    Ok(())
+}
+
+/// Enforces the reply permission of a tweet, for replies and comments alike. Its author
+/// can always reply, even when nobody else can. For tweets restricted to followed users,
+/// the replier passes the Follow PDA proving the author follows them, like on Twitter
+/// where "people you follow" can reply. For tweets restricted to followers, they pass
+/// their own Follow PDA of the author instead.
+fn ensure_reply_allowed(
+    parent: &Tweet,
+    user: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    if parent.author == *user {
+        return Ok(());
+    }
+
+    let allowed = match parent.reply_permission {
+        REPLY_FOLLOWING => is_following(&parent.author, user, remaining_accounts, program_id)?,
+        REPLY_MENTIONED => parent.mentions.contains(user),
+        REPLY_FOLLOWERS => is_following(user, &parent.author, remaining_accounts, program_id)?,
+        REPLY_NOBODY => false,
+        _ => true,
+    };
+
+    if !allowed {
+        return Err(error!(ErrorCode::RepliesRestricted));
+    }
+
+    Ok(())
+}
+
+/// Whether the Follow PDA of `follower` following `followed` was passed and exists. It is
+/// looked up by its derived address so no other account can stand in for it.
+fn is_following(
+    follower: &Pubkey,
+    followed: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<bool> {
+    let (follow_key, _) = Pubkey::find_program_address(
+        &[FOLLOW_SEED, follower.as_ref(), followed.as_ref()],
+        program_id,
+    );
+    match remaining_accounts
+        .iter()
+        .find(|info| *info.key == follow_key)
+    {
+        Some(follow_info) if !follow_info.data_is_empty() => {
+            load_account::<Follow>(follow_info, program_id)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Checks an entry of `send_tweets_batch` before its account is created. The account must
+/// be a new keypair, still owned by the system program with no data, that signs for its
+/// creation.
+/// Returns the normalized topic and content of the entry.
+fn validate_batch_entry(
+    input: &TweetInput,
+    tweet_info: &AccountInfo,
+    config: &Config,
+) -> Result<(String, String)> {
+    if !tweet_info.is_signer
+        || !tweet_info.data_is_empty()
+        || *tweet_info.owner != system_program::ID
+    {
+        return Err(error!(ErrorCode::InvalidBatchAccount));
+    }
+
+    let (topic, content) = normalize_tweet(&input.topic, &input.content)?;
+    ensure_tweet_limits(&topic, &content, config)?;
+
+    Ok((topic, content))
+}
+
+/// Delegates act for a profile owner by passing the Delegate PDA of the owner and the signer
+/// as the next remaining account. It is only trusted once its address matches those seeds
+/// and it is an initialized Delegate account owned by us, so a removed delegate is rejected
+/// right away.
+fn ensure_delegate<'a, 'info: 'a>(
+    owner: &Pubkey,
+    signer: &Pubkey,
+    remaining_accounts: &mut Peekable<impl Iterator<Item = &'a AccountInfo<'info>>>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (delegate_key, _) = Pubkey::find_program_address(
+        &[DELEGATE_SEED, owner.as_ref(), signer.as_ref()],
+        program_id,
+    );
+    match remaining_accounts.next_if(|info| *info.key == delegate_key) {
+        Some(delegate_info) if !delegate_info.data_is_empty() => {
+            load_account::<Delegate>(delegate_info, program_id)?;
+            Ok(())
+        }
+        _ => Err(error!(ErrorCode::NotADelegate)),
+    }
+}
+
+/// Lets the author of a tweet act on it, or one of their delegates through `ensure_delegate`.
+fn ensure_author_or_delegate<'a, 'info: 'a>(
+    author: &Pubkey,
+    signer: &Pubkey,
+    remaining_accounts: &mut Peekable<impl Iterator<Item = &'a AccountInfo<'info>>>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if author == signer {
+        return Ok(());
+    }
+
+    ensure_delegate(author, signer, remaining_accounts, program_id)
+}
+
+/// Reads the public key and message verified by the Ed25519 program instruction placed
+/// right before the current one. The runtime already failed the transaction if the signature
+/// was invalid, so what is left is making sure that instruction exists and verifies a single
+/// signature whose key and message are stored in its own data.
+fn verified_ed25519_message(instructions: &AccountInfo) -> Result<(Pubkey, Vec<u8>)> {
+    let current_index = load_current_index_checked(instructions)?;
+    if current_index == 0 {
+        return Err(error!(ErrorCode::MissingSignature));
+    }
+    let instruction = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    let data = &instruction.data;
+    if instruction.program_id != ed25519_program::ID
+        || data.len() < ED25519_HEADER_LENGTH
+        || data[0] != 1
+    {
+        return Err(error!(ErrorCode::MissingSignature));
+    }
+
+    // The header is the signature count and a padding byte followed by seven u16 offsets.
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let signature_instruction = read_u16(4);
+    let public_key_offset = read_u16(6);
+    let public_key_instruction = read_u16(8);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+    let message_instruction = read_u16(14);
+    let in_own_data = |index: usize| index == u16::MAX as usize;
+    if !in_own_data(signature_instruction)
+        || !in_own_data(public_key_instruction)
+        || !in_own_data(message_instruction)
+    {
+        return Err(error!(ErrorCode::MissingSignature));
+    }
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBLIC_KEY_LENGTH)
+        .ok_or(ErrorCode::MissingSignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::MissingSignature)?;
+
+    Ok((Pubkey::new(public_key), message.to_vec()))
+}
+
+/// Only members can post into a space. The author proves their membership with their
+/// SpaceMember PDA, passed right after the Space, so a removed member is rejected.
+fn post_to_space<'a, 'info: 'a>(
+    space_key: Pubkey,
+    author: &Pubkey,
+    remaining_accounts: &mut Peekable<impl Iterator<Item = &'a AccountInfo<'info>>>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let space_info = remaining_accounts
+        .next_if(|info| *info.key == space_key)
+        .ok_or(ErrorCode::SpaceRequired)?;
+    let (member_key, _) = Pubkey::find_program_address(
+        &[SPACE_MEMBER_SEED, space_key.as_ref(), author.as_ref()],
+        program_id,
+    );
+    match remaining_accounts.next_if(|info| *info.key == member_key) {
+        Some(member_info) if !member_info.data_is_empty() => {
+            load_account::<SpaceMember>(member_info, program_id)?;
+        }
+        _ => return Err(error!(ErrorCode::NotSpaceMember)),
+    }
+
+    let mut space: Space = load_account(space_info, program_id)?;
+    space.tweet_count.increment()?;
+    store_account(space_info, &space)
+}
+
+/// Token gates only restrict interactions: the content of every tweet can be read off
+/// chain by anyone, so they cannot hide it. The user proves they hold enough gate tokens
+/// with their token account of the gate mint, the first account of the token program in
+/// the remaining accounts. Anchor 0.22 has no optional accounts, so it is loaded as an
+/// `Account<TokenAccount>` here and gets the checks its constraints would have.
+fn ensure_token_holder(
+    tweet: &Tweet,
+    user: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let gate_mint = match tweet.gate_mint {
+        Some(gate_mint) if tweet.author != *user => gate_mint,
+        _ => return Ok(()),
+    };
+
+    let gate_info = remaining_accounts
+        .iter()
+        .find(|info| *info.owner == token::ID)
+        .ok_or(ErrorCode::NotTokenHolder)?;
+    let gate_account: Account<TokenAccount> = Account::try_from(gate_info)?;
+
+    if gate_account.owner != *user {
+        return Err(error!(ErrorCode::TokenOwnerMismatch));
+    }
+
+    if gate_account.mint != gate_mint {
+        return Err(error!(ErrorCode::TokenMintMismatch));
+    }
+
+    if gate_account.amount < tweet.gate_min_amount {
+        return Err(error!(ErrorCode::NotTokenHolder));
+    }
+
+    Ok(())
+}
+
+/// Increments the Hashtag PDA of every hashtag in the content, creating the missing ones.
+fn record_hashtags<'a, 'info: 'a>(
+    content: &str,
+    remaining_accounts: &mut impl Iterator<Item = &'a AccountInfo<'info>>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    for tag in extract_hashtags(content) {
+        let hashtag_info = remaining_accounts
+            .next()
+            .ok_or(ErrorCode::HashtagAccountMismatch)?;
+        let (hashtag_key, bump) =
+            Pubkey::find_program_address(&[HASHTAG_SEED, tag.as_bytes()], program_id);
+        if *hashtag_info.key != hashtag_key {
+            return Err(error!(ErrorCode::HashtagAccountMismatch));
+        }
+
+        if hashtag_info.data_is_empty() {
+            create_pda_account(
+                payer,
+                hashtag_info,
+                system_program,
+                Hashtag::LEN,
+                &[HASHTAG_SEED, tag.as_bytes(), &[bump]],
+            )?;
+            let hashtag = Hashtag {
+                tag,
+                tweet_count: 1,
+                bump,
+            };
+            store_account(hashtag_info, &hashtag)?;
+        } else {
+            let mut hashtag: Hashtag = load_account(hashtag_info, program_id)?;
+            hashtag.tweet_count.increment()?;
+            store_account(hashtag_info, &hashtag)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a Mention PDA for every user mentioned by the tweet.
+fn record_mentions<'a, 'info: 'a>(
+    tweet: &Account<Tweet>,
+    remaining_accounts: &mut impl Iterator<Item = &'a AccountInfo<'info>>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    for mentioned in tweet.mentions.iter() {
+        let mention_info = remaining_accounts
+            .next()
+            .ok_or(ErrorCode::MentionAccountMismatch)?;
+        let (mention_key, bump) = Pubkey::find_program_address(
+            &[MENTION_SEED, mentioned.as_ref(), tweet.key().as_ref()],
+            program_id,
+        );
+        if *mention_info.key != mention_key {
+            return Err(error!(ErrorCode::MentionAccountMismatch));
+        }
+
+        create_pda_account(
+            payer,
+            mention_info,
+            system_program,
+            Mention::LEN,
+            &[
+                MENTION_SEED,
+                mentioned.as_ref(),
+                tweet.key().as_ref(),
+                &[bump],
+            ],
+        )?;
+        let mention = Mention {
+            mentioned: *mentioned,
+            tweet: tweet.key(),
+            author: tweet.author,
+            timestamp: tweet.timestamp,
+            bump,
+        };
+        store_account(mention_info, &mention)?;
+    }
+
+    Ok(())
+}
+
+/// Appends a tweet to the author's current index page, rolling over to a new page once full.
+fn append_to_author_index<'info>(
+    profile: &mut UserProfile,
+    tweet: Pubkey,
+    index_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let page_bytes = profile.index_page.to_le_bytes();
+    let (index_key, bump) = Pubkey::find_program_address(
+        &[AUTHOR_INDEX_SEED, profile.authority.as_ref(), &page_bytes],
+        program_id,
+    );
+    if *index_info.key != index_key {
+        return Err(error!(ErrorCode::IndexPageMismatch));
+    }
+
+    let mut index: AuthorIndex = if index_info.data_is_empty() {
+        create_pda_account(
+            payer,
+            index_info,
+            system_program,
+            AuthorIndex::LEN,
+            &[
+                AUTHOR_INDEX_SEED,
+                profile.authority.as_ref(),
+                &page_bytes,
+                &[bump],
+            ],
+        )?;
+        AuthorIndex {
+            author: profile.authority,
+            page: profile.index_page,
+            tweets: Vec::new(),
+            bump,
+            version: 0,
+        }
+    } else {
+        load_account(index_info, program_id)?
+    };
+
+    index.tweets.push(tweet);
+    if index.tweets.len() == AUTHOR_INDEX_PAGE_SIZE {
+        profile.index_page.increment()?;
+    }
+
+    store_account(index_info, &index)
+}
+
+/// Every instruction creating a tweet sends the posting fee to the treasury. A zero fee means
+/// posting is free.
+fn charge_post_fee<'info>(
+    config: &Config,
+    payer: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if config.post_fee_lamports == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer.key, treasury.key, config.post_fee_lamports),
+        &[payer.clone(), treasury.clone(), system_program.clone()],
+    )?;
+
+    Ok(())
+}
+
+/// The checks every instruction creating a tweet runs once the tweet is built. Deactivated
+/// profiles cannot post, a profile has to wait the minimum post interval between two tweets
+/// and the author's RecentPost, optional unless a cooldown is active, enforces the cooldown
+/// and rejects duplicate content.
+fn ensure_can_post<'info>(
+    tweet: &Tweet,
+    profile: &mut UserProfile,
+    recent_info: Option<&AccountInfo<'info>>,
+    config: &Config,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    ensure_profile_active(profile)?;
+    // A profile that never posted has no timestamp yet and can always post.
+    if profile.last_post_ts > 0 && tweet.timestamp - profile.last_post_ts < config.min_post_interval
+    {
+        return Err(error!(ErrorCode::PostingTooFast));
+    }
+    profile.last_post_ts = tweet.timestamp;
+
+    match recent_info {
+        Some(recent_info) => record_recent_post(
+            tweet,
+            recent_info,
+            config,
+            payer,
+            system_program,
+            program_id,
+        ),
+        None if config.tweet_cooldown_secs > 0 => Err(error!(ErrorCode::RecentPostRequired)),
+        None => Ok(()),
+    }
+}
+
+fn recent_post_address(author: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECENT_POST_SEED, author.as_ref()], program_id)
+}
+
+/// Rejects the tweet if its author posted the same content within the duplicate window.
+fn record_recent_post<'info>(
+    tweet: &Tweet,
+    recent_info: &AccountInfo<'info>,
+    config: &Config,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (_, bump) = recent_post_address(&tweet.author, program_id);
+    if recent_info.data_is_empty() {
+        create_pda_account(
+            payer,
+            recent_info,
+            system_program,
+            RecentPost::LEN,
+            &[RECENT_POST_SEED, tweet.author.as_ref(), &[bump]],
+        )?;
+    } else {
+        let recent_post: RecentPost = load_account(recent_info, program_id)?;
+        let elapsed = tweet.timestamp - recent_post.timestamp;
+        if recent_post.content_hash == tweet.content_hash && elapsed < config.duplicate_window_secs
+        {
+            return Err(error!(ErrorCode::DuplicateContent));
+        }
+
+        // A cooldown of zero disables rate limiting.
+        let cooldown_secs = config.tweet_cooldown_secs as i64;
+        if elapsed < cooldown_secs {
+            msg!(
+                "Cooldown active, {} seconds remaining",
+                cooldown_secs - elapsed
+            );
+            return Err(error!(ErrorCode::CooldownActive));
+        }
+    }
+
+    let recent_post = RecentPost {
+        author: tweet.author,
+        content_hash: tweet.content_hash,
+        timestamp: tweet.timestamp,
+        bump,
+    };
+    store_account(recent_info, &recent_post)
+}
+
+/// Topics can be longer than a seed allows so the TopicIndex is derived from their hash.
+fn topic_index_address(topic: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TOPIC_INDEX_SEED, &hash(topic.as_bytes()).to_bytes()],
+        program_id,
+    )
+}
+
+/// Counts the tweet in the TopicIndex and keeps it among its most recent tweets.
+fn add_to_topic_index<'info>(
+    topic: &str,
+    tweet: Pubkey,
+    topic_info: &AccountInfo<'info>,
+    bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let mut topic_index: TopicIndex = if topic_info.data_is_empty() {
+        create_pda_account(
+            payer,
+            topic_info,
+            system_program,
+            TopicIndex::LEN,
+            &[
+                TOPIC_INDEX_SEED,
+                &hash(topic.as_bytes()).to_bytes(),
+                &[bump],
+            ],
+        )?;
+        TopicIndex {
+            topic: topic.to_string(),
+            tweet_count: 0,
+            recent_tweets: Vec::new(),
+            bump,
+            version: 0,
+        }
+    } else {
+        load_account(topic_info, program_id)?
+    };
+
+    topic_index.tweet_count.increment()?;
+    if topic_index.recent_tweets.len() == TOPIC_INDEX_CAPACITY {
+        topic_index.recent_tweets.remove(0);
+    }
+    topic_index.recent_tweets.push(tweet);
+
+    store_account(topic_info, &topic_index)
+}
+
+/// Uncounts the tweet from the TopicIndex and drops it from its most recent tweets.
+/// When the topic of a tweet changes, the TopicIndex accounts of the old and new topics can
+/// be passed as remaining accounts, in that order, to move the tweet between them.
+fn move_topic_index<'a, 'info: 'a>(
+    tweet: Pubkey,
+    old_topic: &str,
+    new_topic: &str,
+    remaining_accounts: &mut Peekable<impl Iterator<Item = &'a AccountInfo<'info>>>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if old_topic == new_topic {
+        return Ok(());
+    }
+
+    if !old_topic.is_empty() {
+        let (old_key, _) = topic_index_address(old_topic, program_id);
+        if let Some(old_info) = remaining_accounts.next_if(|info| *info.key == old_key) {
+            remove_from_topic_index(tweet, old_info, program_id)?;
+        }
+    }
+
+    if !new_topic.is_empty() {
+        let (new_key, bump) = topic_index_address(new_topic, program_id);
+        if let Some(new_info) = remaining_accounts.next_if(|info| *info.key == new_key) {
+            add_to_topic_index(
+                new_topic,
+                tweet,
+                new_info,
+                bump,
+                payer,
+                system_program,
+                program_id,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_from_topic_index(
+    tweet: Pubkey,
+    topic_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let mut topic_index: TopicIndex = load_account(topic_info, program_id)?;
+
+    topic_index.tweet_count.decrement()?;
+    topic_index.recent_tweets.retain(|key| *key != tweet);
+
+    store_account(topic_info, &topic_index)
+}
+
+/// Returns the distinct lowercase hashtags of the content, skipping tags too long for a seed.
+fn extract_hashtags(content: &str) -> Vec<String> {
+    let mut hashtags: Vec<String> = Vec::new();
+
+    for word in content.split_whitespace() {
+        if let Some(word) = word.strip_prefix('#') {
+            let tag: String = word
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+                .to_lowercase();
+            if !tag.is_empty() && tag.len() <= MAX_HASHTAG_LENGTH && !hashtags.contains(&tag) {
+                hashtags.push(tag);
+            }
+        }
+    }
+
+    hashtags
+}
+
+#[derive(Accounts)]
+#[instruction(
+    topic: String,
+    content: String,
+    mentions: Vec<Pubkey>,
+    expires_at: Option<i64>,
+    reply_permission: u8,
+    scheduled_reveal: Option<i64>,
+    media: Vec<MediaAttachment>
+)]
+pub struct SendTweet<'info> {
+    // Sized for the attachments actually sent rather than the maximum.
+    #[account(init, payer = author, space = Tweet::LEN + Tweet::media_space(&media))]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTweet<'info> {
+    // The signer is the author or one of their delegates, checked by the instruction.
+    // Shrinking refunds whoever paid for the tweet, not the signer.
+    #[account(
+        mut,
+        has_one = posted_by,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted,
+        constraint = tweet.storage_kind == STORAGE_INLINE @ ErrorCode::RefTweetNotEditable
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = author,
+        space = TweetRevision::LEN,
+        seeds = [REVISION_SEED, tweet.key().as_ref(), &tweet.revision_count.to_le_bytes()],
+        bump
+    )]
+    pub revision: Account<'info, TweetRevision>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = EditLog::LEN,
+        seeds = [EDIT_LOG_SEED, tweet.key().as_ref()],
+        bump
+    )]
+    pub edit_log: Account<'info, EditLog>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTopic<'info> {
+    #[account(
+        mut,
+        has_one = author,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    // Pays for the TopicIndex of the new topic when it does not exist yet.
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteTweet<'info> {
+    // Comments and replies point to their parent tweet so it cannot be closed while it
+    // still has some. The signer is the author or one of their delegates, checked by the
+    // instruction. The rent goes back to whoever paid for the tweet, not to the signer.
+    #[account(
+        mut,
+        has_one = posted_by,
+        close = posted_by,
+        constraint = tweet.comments == 0 @ ErrorCode::TweetHasComments,
+        constraint = tweet.reply_count == 0 @ ErrorCode::TweetHasReplies
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProfile<'info> {
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump = profile.bump,
+        has_one = authority
+    )]
+    pub profile: Account<'info, UserProfile>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProfile<'info> {
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump = profile.bump,
+        has_one = authority,
+        close = authority,
+        constraint = profile.tweet_count == 0 @ ErrorCode::ProfileNotEmpty
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimProfile<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump = profile.bump,
+        has_one = authority,
+        close = authority,
+        constraint = profile.tweet_count == 0 @ ErrorCode::ProfileNotEmpty
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub authority: SystemAccount<'info>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LikeTweet<'info> {
+    #[account(
+        init,
+        payer = liker,
+        space = Like::LEN,
+        seeds = [LIKE_SEED, tweet.key().as_ref(), liker.key().as_ref()],
+        bump
+    )]
+    pub like: Account<'info, Like>,
+    #[account(
+        mut,
+        constraint = tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !tweet.hidden @ ErrorCode::TweetHidden,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, tweet.author.as_ref()],
+        bump = author_profile.bump
+    )]
+    pub author_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub liker: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordView<'info> {
+    #[account(
+        init_if_needed,
+        payer = viewer,
+        space = View::LEN,
+        seeds = [VIEW_SEED, tweet.key().as_ref(), viewer.key().as_ref()],
+        bump
+    )]
+    pub view: Account<'info, View>,
+    #[account(mut, constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub viewer: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordImpressions<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.oracle == oracle.key() @ ErrorCode::NotOracle
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    pub oracle: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proof: Vec<[u8; 32]>, leaf_user: Pubkey)]
+pub struct ClaimImportedLike<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Like::LEN,
+        seeds = [LIKE_SEED, tweet.key().as_ref(), leaf_user.as_ref()],
+        bump
+    )]
+    pub like: Account<'info, Like>,
+    #[account(
+        init,
+        payer = payer,
+        space = LikeClaim::LEN,
+        seeds = [LIKE_CLAIM_SEED, tweet.key().as_ref(), leaf_user.as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, LikeClaim>,
+    #[account(mut, constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnlikeTweet<'info> {
+    #[account(
+        mut,
+        seeds = [LIKE_SEED, tweet.key().as_ref(), liker.key().as_ref()],
+        bump = like.bump,
+        has_one = tweet,
+        has_one = liker,
+        close = liker
+    )]
+    pub like: Account<'info, Like>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, tweet.author.as_ref()],
+        bump = author_profile.bump
+    )]
+    pub author_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub liker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendComment<'info> {
+    #[account(init, payer = author, space = Comment::LEN)]
+    pub comment: Account<'info, Comment>,
+    #[account(
+        mut,
+        constraint = parent_tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !parent_tweet.hidden @ ErrorCode::TweetHidden,
+        constraint = !parent_tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub parent_tweet: Account<'info, Tweet>,
+    // Required so a deactivated author cannot comment by leaving it out.
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteComment<'info> {
+    #[account(mut, has_one = author, has_one = parent_tweet, close = author)]
+    pub comment: Account<'info, Comment>,
+    #[account(mut)]
+    pub parent_tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FollowUser<'info> {
+    #[account(
+        init,
+        payer = follower,
+        space = Follow::LEN,
+        seeds = [FOLLOW_SEED, follower.key().as_ref(), followed_profile.authority.as_ref()],
+        bump
+    )]
+    pub follow: Account<'info, Follow>,
+    #[account(
+        init_if_needed,
+        payer = follower,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, follower.key().as_ref()],
+        bump
+    )]
+    pub follower_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub followed_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub follower: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnfollowUser<'info> {
+    #[account(
+        mut,
+        seeds = [FOLLOW_SEED, follower.key().as_ref(), followed_profile.authority.as_ref()],
+        bump = follow.bump,
+        has_one = follower,
+        close = follower
+    )]
+    pub follow: Account<'info, Follow>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, follower.key().as_ref()],
+        bump = follower_profile.bump
+    )]
+    pub follower_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub followed_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub follower: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Reply<'info> {
+    #[account(init, payer = author, space = Tweet::LEN)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(
+        mut,
+        constraint = parent.published @ ErrorCode::TweetNotPublished,
+        constraint = !parent.hidden @ ErrorCode::TweetHidden,
+        constraint = !parent.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub parent: Account<'info, Tweet>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RetweetTweet<'info> {
+    #[account(
+        init,
+        payer = retweeter,
+        space = Retweet::LEN,
+        seeds = [RETWEET_SEED, original_tweet.key().as_ref(), retweeter.key().as_ref()],
+        bump
+    )]
+    pub retweet: Account<'info, Retweet>,
+    #[account(
+        mut,
+        constraint = original_tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !original_tweet.hidden @ ErrorCode::TweetHidden,
+        constraint = !original_tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub original_tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub retweeter: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UndoRetweet<'info> {
+    #[account(
+        mut,
+        seeds = [RETWEET_SEED, original_tweet.key().as_ref(), retweeter.key().as_ref()],
+        bump = retweet.bump,
+        has_one = original_tweet,
+        has_one = retweeter,
+        close = retweeter
+    )]
+    pub retweet: Account<'info, Retweet>,
+    #[account(mut)]
+    pub original_tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub retweeter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteTweet<'info> {
+    #[account(init, payer = author, space = Tweet::LEN)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(
+        mut,
+        constraint = quoted_tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !quoted_tweet.hidden @ ErrorCode::TweetHidden,
+        constraint = !quoted_tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub quoted_tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(handle: String)]
+pub struct RegisterHandle<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Handle::LEN,
+        seeds = [HANDLE_SEED, handle.as_bytes()],
+        bump
+    )]
+    pub handle_account: Account<'info, Handle>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHandle<'info> {
+    #[account(
+        mut,
+        seeds = [HANDLE_SEED, handle_account.handle.as_bytes()],
+        bump = handle_account.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub handle_account: Account<'info, Handle>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferHandle<'info> {
+    #[account(
+        mut,
+        seeds = [HANDLE_SEED, handle_account.handle.as_bytes()],
+        bump = handle_account.bump,
+        has_one = owner
+    )]
+    pub handle_account: Account<'info, Handle>,
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct TipTweet<'info> {
+    #[account(
+        mut,
+        has_one = author,
+        constraint = tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub author: SystemAccount<'info>,
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PinTweet<'info> {
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump = profile.bump,
+        has_one = authority
+    )]
+    pub profile: Account<'info, UserProfile>,
+    pub tweet: Account<'info, Tweet>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct UnpinTweet<'info> {
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump = profile.bump,
+        has_one = authority
+    )]
+    pub profile: Account<'info, UserProfile>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TipTweetSpl<'info> {
+    #[account(
+        mut,
+        constraint = tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = tipper,
+        space = TipStats::LEN,
+        seeds = [TIP_STATS_SEED, tweet.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub tip_stats: Account<'info, TipStats>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = tipper_token_account.mint == mint.key() @ ErrorCode::TokenMintMismatch,
+        constraint = tipper_token_account.owner == tipper.key() @ ErrorCode::TokenOwnerMismatch
+    )]
+    pub tipper_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = author_token_account.mint == mint.key() @ ErrorCode::TokenMintMismatch,
+        constraint = author_token_account.owner == tweet.author @ ErrorCode::TokenOwnerMismatch
+    )]
+    pub author_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendIndexedTweet<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = Tweet::LEN,
+        seeds = [TWEET_SEED, author.key().as_ref(), &profile.tweet_count.to_le_bytes()],
+        bump
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump = profile.bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BookmarkTweet<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = Bookmark::LEN,
+        seeds = [BOOKMARK_SEED, user.key().as_ref(), tweet.key().as_ref()],
+        bump
+    )]
+    pub bookmark: Account<'info, Bookmark>,
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveBookmark<'info> {
+    #[account(
+        mut,
+        seeds = [BOOKMARK_SEED, user.key().as_ref(), bookmark.tweet.as_ref()],
+        bump = bookmark.bump,
+        has_one = user,
+        close = user
+    )]
+    pub bookmark: Account<'info, Bookmark>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferOwnership<'info> {
+    #[account(mut, has_one = author)]
+    pub tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePoll<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = Poll::LEN,
+        seeds = [POLL_SEED, tweet.key().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(has_one = author)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VotePoll<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.tweet.as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    // One vote per voter, voting twice fails when initializing this account again.
+    #[account(
+        init,
+        payer = voter,
+        space = PollVote::LEN,
+        seeds = [POLL_VOTE_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub poll_vote: Account<'info, PollVote>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BlockUser<'info> {
+    #[account(
+        init,
+        payer = blocker,
+        space = Block::LEN,
+        seeds = [BLOCK_SEED, blocker.key().as_ref(), blocked.key().as_ref()],
+        bump
+    )]
+    pub block: Account<'info, Block>,
+    #[account(mut)]
+    pub blocker: Signer<'info>,
+    /// CHECK: This is not dangerous because we only use the address of the blocked user
+    pub blocked: AccountInfo<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnblockUser<'info> {
+    #[account(
+        mut,
+        seeds = [BLOCK_SEED, blocker.key().as_ref(), block.blocked.as_ref()],
+        bump = block.bump,
+        has_one = blocker,
+        close = blocker
+    )]
+    pub block: Account<'info, Block>,
+    #[account(mut)]
+    pub blocker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct AddDelegate<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Delegate::LEN,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegate>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), delegation.delegate.as_ref()],
+        bump = delegation.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub delegation: Account<'info, Delegate>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // The config is a singleton so it can only be initialized once, by its admin.
+    #[account(init, payer = admin, space = Config::LEN, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, Config>,
+    #[account(init, payer = admin, space = Treasury::LEN, seeds = [TREASURY_SEED], bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(init, payer = admin, space = GlobalStats::LEN, seeds = [STATS_SEED], bump)]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVerified<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, profile.authority.as_ref()],
+        bump = profile.bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SoftDeleteTweet<'info> {
+    #[account(
+        mut,
+        has_one = author,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimSoftDeleted<'info> {
+    // Like `DeleteTweet`, the tombstone stays until no reply points to it anymore.
+    #[account(
+        mut,
+        has_one = author,
+        has_one = posted_by,
+        close = posted_by,
+        constraint = tweet.is_deleted @ ErrorCode::TweetNotSoftDeleted,
+        constraint = tweet.comments == 0 @ ErrorCode::TweetHasComments,
+        constraint = tweet.reply_count == 0 @ ErrorCode::TombstoneHasReplies
+    )]
+    pub tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportTweet<'info> {
+    #[account(
+        init,
+        payer = reporter,
+        space = Report::LEN,
+        seeds = [REPORT_SEED, tweet.key().as_ref(), reporter.key().as_ref()],
+        bump
+    )]
+    pub report: Account<'info, Report>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReport<'info> {
+    #[account(
+        mut,
+        seeds = [REPORT_SEED, tweet.key().as_ref(), reporter.key().as_ref()],
+        bump = report.bump,
+        has_one = reporter,
+        has_one = tweet,
+        close = reporter
+    )]
+    pub report: Account<'info, Report>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ImportLikesRoot<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ModerateTweet<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendTweetsBatch<'info> {
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteTweetsBatch<'info> {
+    // The tweets come through the remaining accounts and must all belong to the signer.
+    #[account(mut)]
+    pub author: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TweetInput {
+    pub topic: String,
+    pub content: String,
+}
+
+#[derive(Accounts)]
+pub struct CloseRevision<'info> {
+    // Revisions outlive their tweet so they are closed on their own, by the author.
+    #[account(
+        mut,
+        seeds = [REVISION_SEED, revision.tweet.as_ref(), &revision.index.to_le_bytes()],
+        bump = revision.bump,
+        has_one = author,
+        close = author
+    )]
+    pub revision: Account<'info, TweetRevision>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleTweet<'info> {
+    #[account(init, payer = author, space = Tweet::LEN)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(topic: String, content: String)]
+pub struct SendLongTweet<'info> {
+    #[account(init, payer = author, space = Tweet::LEN + Tweet::long_form_space(&content))]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendGatedTweet<'info> {
+    #[account(init, payer = author, space = Tweet::LEN)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        constraint = config.gate_mint == Some(gate_mint.key()) @ ErrorCode::GateNotSatisfied
+    )]
+    pub gate_mint: Account<'info, Mint>,
+    #[account(
+        constraint = author_token_account.owner == author.key() @ ErrorCode::GateNotSatisfied,
+        constraint = author_token_account.mint == gate_mint.key() @ ErrorCode::GateNotSatisfied,
+        constraint = author_token_account.amount >= 1 @ ErrorCode::GateNotSatisfied
+    )]
+    pub author_token_account: Account<'info, TokenAccount>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_uri: String)]
+pub struct SendTweetRef<'info> {
+    #[account(init, payer = author, space = Tweet::ref_space(&content_uri))]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = author,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendTweetSponsored<'info> {
+    #[account(init, payer = relayer, space = Tweet::LEN)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserProfile::LEN,
+        seeds = [PROFILE_SEED, author.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+    /// CHECK: The author does not sign the transaction, the instruction checks their
+    /// signature of the tweet through the Ed25519 program instead.
+    pub author: AccountInfo<'info>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = sysvar::instructions::ID)]
+    /// CHECK: This is the instructions sysvar, checked by its address
+    pub instructions: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+/// What an author signs for `send_tweet_sponsored`, serialized with Borsh.
+#[derive(AnchorSerialize)]
+struct SponsoredTweetMessage {
+    program_id: Pubkey,
+    nonce: u64,
+    topic: String,
+    content: String,
+}
+
+#[derive(Accounts)]
+pub struct PublishTweet<'info> {
+    #[account(mut, constraint = !tweet.published @ ErrorCode::TweetAlreadyPublished)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RevealTweet<'info> {
+    #[account(mut, constraint = !tweet.is_revealed @ ErrorCode::TweetAlreadyRevealed)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelScheduledTweet<'info> {
+    #[account(
+        mut,
+        has_one = author,
+        has_one = posted_by,
+        close = posted_by,
+        constraint = !tweet.published @ ErrorCode::TweetAlreadyPublished
+    )]
+    pub tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MuteUser<'info> {
+    #[account(
+        init,
+        payer = muter,
+        space = Mute::LEN,
+        seeds = [MUTE_SEED, muter.key().as_ref(), muted.key().as_ref()],
+        bump
+    )]
+    pub mute: Account<'info, Mute>,
+    #[account(mut)]
+    pub muter: Signer<'info>,
+    /// CHECK: This is not dangerous because we only use the address of the muted user
+    pub muted: AccountInfo<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnmuteUser<'info> {
+    #[account(
+        mut,
+        seeds = [MUTE_SEED, muter.key().as_ref(), mute.muted.as_ref()],
+        bump,
+        has_one = muter,
+        close = muter
+    )]
+    pub mute: Account<'info, Mute>,
+    #[account(mut)]
+    pub muter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReapExpiredTweet<'info> {
+    #[account(
+        mut,
+        has_one = posted_by,
+        close = posted_by,
+        constraint = tweet.comments == 0 @ ErrorCode::TweetHasComments,
+        constraint = tweet.reply_count == 0 @ ErrorCode::TombstoneHasReplies
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReallocTweet<'info> {
+    #[account(mut, has_one = author, has_one = posted_by)]
+    pub tweet: Account<'info, Tweet>,
+    pub author: Signer<'info>,
+    #[account(mut)]
+    pub posted_by: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct SendDm<'info> {
+    #[account(init, payer = sender, space = Message::LEN)]
+    pub message: Account<'info, Message>,
+    // Both participants share the same conversation whoever sends the message.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = Conversation::LEN,
+        seeds = [
+            CONVERSATION_SEED,
+            sender.key().min(recipient).as_ref(),
+            sender.key().max(recipient).as_ref()
+        ],
+        bump
+    )]
+    pub conversation: Account<'info, Conversation>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteDm<'info> {
+    #[account(mut, has_one = sender, has_one = conversation, close = sender)]
+    pub message: Account<'info, Message>,
+    #[account(mut)]
+    pub conversation: Account<'info, Conversation>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTweet<'info> {
+    /// CHECK: Older tweets may not deserialize before they are grown, the instruction checks
+    /// the owner, the discriminator and the author itself.
+    #[account(mut)]
+    pub tweet: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    /// CHECK: The instruction checks it is who posted the tweet, it only pays for the growth.
+    #[account(mut)]
+    pub posted_by: UncheckedAccount<'info>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintTweetNft<'info> {
+    #[account(
+        mut,
+        has_one = author,
+        constraint = tweet.nft_mint.is_none() @ ErrorCode::AlreadyMinted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(
+        init,
+        payer = author,
+        mint::decimals = 0,
+        mint::authority = mint_authority
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [MINT_AUTHORITY_SEED], bump)]
+    /// CHECK: This is not dangerous because it only signs for the mint and its metadata
+    pub mint_authority: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = author,
+        associated_token::mint = mint,
+        associated_token::authority = author
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because the metadata program checks its address
+    pub metadata: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: This is not dangerous because the metadata program checks its address
+    pub master_edition: AccountInfo<'info>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = mpl_token_metadata::ID)]
+    /// CHECK: This is not dangerous because its address is checked
+    pub token_metadata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Subscribe<'info> {
+    #[account(
+        init,
+        payer = subscriber,
+        space = Subscription::LEN,
+        seeds = [SUBSCRIPTION_SEED, creator.key().as_ref(), subscriber.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+    #[account(seeds = [PROFILE_SEED, creator.key().as_ref()], bump = creator_profile.bump)]
+    pub creator_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RenewSubscription<'info> {
+    #[account(
+        mut,
+        seeds = [SUBSCRIPTION_SEED, creator.key().as_ref(), subscriber.key().as_ref()],
+        bump = subscription.bump,
+        has_one = creator,
+        has_one = subscriber
+    )]
+    pub subscription: Account<'info, Subscription>,
+    #[account(seeds = [PROFILE_SEED, creator.key().as_ref()], bump = creator_profile.bump)]
+    pub creator_profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReactToTweet<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Reaction::LEN,
+        seeds = [REACTION_SEED, tweet.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub reaction: Account<'info, Reaction>,
+    #[account(
+        mut,
+        constraint = tweet.published @ ErrorCode::TweetNotPublished,
+        constraint = !tweet.hidden @ ErrorCode::TweetHidden,
+        constraint = !tweet.is_deleted @ ErrorCode::TweetSoftDeleted
+    )]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveReaction<'info> {
+    #[account(
+        mut,
+        seeds = [REACTION_SEED, tweet.key().as_ref(), user.key().as_ref()],
+        bump = reaction.bump,
+        has_one = tweet,
+        has_one = user,
+        close = user
+    )]
+    pub reaction: Account<'info, Reaction>,
+    #[account(mut)]
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateList<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = List::LEN,
+        seeds = [LIST_SEED, owner.key().as_ref(), &[profile.list_count]],
+        bump
+    )]
+    pub list: Account<'info, List>,
+    #[account(mut, seeds = [PROFILE_SEED, owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct AddListMember<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotListOwner)]
+    pub list: Account<'info, List>,
+    #[account(
+        init,
+        payer = owner,
+        space = ListMember::LEN,
+        seeds = [LIST_MEMBER_SEED, list.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub list_member: Account<'info, ListMember>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveListMember<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotListOwner)]
+    pub list: Account<'info, List>,
+    #[account(
+        mut,
+        seeds = [LIST_MEMBER_SEED, list.key().as_ref(), list_member.member.as_ref()],
+        bump = list_member.bump,
+        has_one = list,
+        close = owner
+    )]
+    pub list_member: Account<'info, ListMember>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteList<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::NotListOwner,
+        close = owner,
+        constraint = list.member_count == 0 @ ErrorCode::ListNotEmpty
+    )]
+    pub list: Account<'info, List>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTweetList<'info> {
+    #[account(init, payer = owner, space = TweetList::LEN)]
+    pub list: Account<'info, TweetList>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddToList<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotListOwner)]
+    pub list: Account<'info, TweetList>,
+    #[account(
+        init,
+        payer = owner,
+        space = ListItem::LEN,
+        seeds = [LIST_ITEM_SEED, list.key().as_ref(), tweet.key().as_ref()],
+        bump
+    )]
+    pub list_item: Account<'info, ListItem>,
+    pub tweet: Account<'info, Tweet>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromList<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotListOwner)]
+    pub list: Account<'info, TweetList>,
+    #[account(
+        mut,
+        seeds = [LIST_ITEM_SEED, list.key().as_ref(), list_item.tweet.as_ref()],
+        bump = list_item.bump,
+        has_one = list,
+        close = owner
+    )]
+    pub list_item: Account<'info, ListItem>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateSpace<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Space::LEN,
+        seeds = [SPACE_SEED, &hash(name.as_bytes()).to_bytes()],
+        bump
+    )]
+    pub space: Account<'info, Space>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSpace<'info> {
+    // Not named `space`, which the code generated for `init` uses for the account size.
+    #[account(mut)]
+    pub joined_space: Account<'info, Space>,
+    #[account(
+        init,
+        payer = user,
+        space = SpaceMember::LEN,
+        seeds = [SPACE_MEMBER_SEED, joined_space.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, SpaceMember>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = system_program::ID)]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMember<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotSpaceOwner)]
+    pub space: Account<'info, Space>,
+    #[account(
+        mut,
+        seeds = [SPACE_MEMBER_SEED, space.key().as_ref(), user.key().as_ref()],
+        bump = member.bump,
+        has_one = space,
+        has_one = user,
+        close = user
+    )]
+    pub member: Account<'info, SpaceMember>,
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferSpaceOwnership<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::NotSpaceOwner)]
+    pub space: Account<'info, Space>,
+    pub owner: Signer<'info>,
+}
+
+/// Sent and stored as a single byte, its index in this list, so unknown codes fail to
+/// deserialize before any instruction runs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum ReactionKind {
+    Like,
+    Fire,
+    Laugh,
+    Sad,
+    Angry,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum MediaKind {
+    Image,
+    Video,
+    Gif,
+    Link,
+}
+
+/// Restricts interactions with a tweet to users holding `min_amount` tokens of `mint`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenGate {
+    pub mint: Pubkey,
+    pub min_amount: u64,
+}
+
+/// A co-author of a tweet and their share of its SOL tips, in basis points. The author
+/// keeps whatever the co-authors' shares leave. SPL tips are not split.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CoAuthor {
+    pub key: Pubkey,
+    pub bps: u16,
+}
+
+/// Media are stored off-chain, tweets only keep their content-addressed URI.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MediaAttachment {
+    pub kind: MediaKind,
+    pub uri: String,
+}
+
+/// Tweets are Borsh-serialized, not `zero_copy` with fixed content and topic buffers: their
+/// optional fields, mentions, media, co-authors and growable content (see `realloc_tweet`)
+/// have no fixed-size `Pod` layout. A test pins the allocated size instead.
+#[account]
+pub struct Tweet {
+    pub author: Pubkey,
+    pub timestamp: i64,
+    pub reply_to: Option<Pubkey>,
+    pub topic: String,
+    pub content: String,
+    pub edited_at: Option<i64>,
+    pub like_count: u64,
+    pub comments: u64,
+    pub retweet_count: u64,
+    pub quote_count: u64,
+    pub quoted_tweet: Option<Pubkey>,
+    pub tips_received: u64,
+    pub author_index: Option<u64>,
+    pub reply_count: u64,
+    pub total_tips_spl: u64,
+    // The top-level tweet of the thread of a reply, `None` for top-level tweets which are
+    // their own root. It follows the topic and content so it has no fixed offset.
+    pub thread_root: Option<Pubkey>,
+    pub mentions: Vec<Pubkey>,
+    pub is_deleted: bool,
+    pub content_hash: [u8; 32],
+    pub reports: u32,
+    pub hidden: bool,
+    pub revision_count: u16,
+    pub published: bool,
+    pub publish_at: Option<i64>,
+    pub expires_at: Option<i64>,
+    // Appended rather than first so tweets created before versioning still deserialize.
+    pub version: u8,
+    pub reply_permission: u8,
+    pub scheduled_reveal: Option<i64>,
+    pub is_revealed: bool,
+    pub weighted_like_score: u64,
+    pub media: Vec<MediaAttachment>,
+    pub nft_mint: Option<Pubkey>,
+    // Only set on long-form tweets, regular tweets are short enough to show in full.
+    pub preview: String,
+    pub gate_mint: Option<Pubkey>,
+    pub gate_min_amount: u64,
+    // The author, the delegate who posted on their behalf or the relayer of a sponsored
+    // tweet. It pays for all of the tweet's rent and gets all of it back.
+    pub posted_by: Pubkey,
+    // Whether clients should read `content` or fetch `content_uri`.
+    pub storage_kind: u8,
+    pub content_uri: String,
+    // One counter per `ReactionKind`, in declaration order.
+    pub reaction_counts: [u32; REACTION_KIND_COUNT],
+    // Merkle root of the likes imported from elsewhere and how many it holds.
+    pub imported_likes_root: [u8; 32],
+    pub imported_likes_count: u64,
+    // Views pushed by the oracle and when it last pushed some.
+    pub views: u64,
+    pub last_impression_sync: i64,
+    // Unique wallets that recorded a view, each one through its own `View`.
+    pub view_count: u64,
+    // The space the tweet was posted into, if any.
+    pub space: Option<Pubkey>,
+    // Who shares the tips of the tweet with its author, in declaration order.
+    pub co_authors: Vec<CoAuthor>,
+}
+
+const DISCRIMINATOR_LENGTH: usize = 8;
+const PUBLIC_KEY_LENGTH: usize = 32;
+const TIMESTAMP_LENGTH: usize = 8;
+const STRING_LENGTH_PREFIX: usize = 4; // Stores the size of the string.
+const OPTION_PREFIX_LENGTH: usize = 1; // Stores whether the option is set.
+const MAX_TOPIC_LENGTH: usize = 50 * 4; // 50 chars max.
+const MAX_CONTENT_LENGTH: usize = 280 * 4; // 280 chars max.
+const VERSION_LENGTH: usize = 1;
+const REPLY_PERMISSION_LENGTH: usize = 1;
+const RESERVED_LENGTH: usize = 4; // Spare bytes, new fields are sized on top of them.
+
+// Every account carries a version byte. Tweets are the only accounts whose layout changed
+// since they went live, `migrate_tweet` upgrades them to `TWEET_VERSION`. The others are
+// still at version 0, the layout they were created with, and a new one would bump it.
+const TWEET_VERSION: u8 = 11;
+const REPLY_EVERYONE: u8 = 0;
+const REPLY_FOLLOWING: u8 = 1; // Only users followed by the author.
+const REPLY_MENTIONED: u8 = 2; // Only users mentioned in the tweet.
+const REPLY_FOLLOWERS: u8 = 3; // Only users following the author.
+const REPLY_NOBODY: u8 = 4; // Only the author.
+const STORAGE_KIND_LENGTH: usize = 1;
+const STORAGE_INLINE: u8 = 0; // The content is stored in the tweet.
+const STORAGE_REF: u8 = 1; // The content is stored off chain at `content_uri`.
+const MAX_CONTENT_URI_LENGTH: usize = 128; // 128 bytes max.
+const REACTION_KIND_COUNT: usize = 5; // Like, fire, laugh, sad and angry.
+const REACTION_KIND_LENGTH: usize = 1;
+const REACTION_COUNTER_LENGTH: usize = 4;
+const LIST_INDEX_LENGTH: usize = 1;
+const MAX_LIST_NAME_LENGTH: usize = 32 * 4; // 32 chars max.
+const MAX_LIST_MEMBERS: u64 = 100;
+const MAX_TWEET_LIST_NAME_LENGTH: usize = 50 * 4; // 50 chars max.
+const MAX_TWEET_LIST_DESCRIPTION_LENGTH: usize = 200 * 4; // 200 chars max.
+const MAX_SPACE_NAME_LENGTH: usize = 50 * 4; // 50 chars max.
+const MAX_SPACE_DESCRIPTION_LENGTH: usize = 200 * 4; // 200 chars max.
+const ED25519_HEADER_LENGTH: usize = 16; // Signature count, padding and offsets.
+const MAX_CONFIGURABLE_CONTENT_CHARS: u16 = 1000; // Reachable by growing tweets.
+const MAX_LONG_CONTENT_CHARS: usize = 4000;
+const MAX_PREVIEW_CHARS: usize = 280;
+const COUNTER_LENGTH: usize = 8;
+const LAMPORTS_LENGTH: usize = 8;
+const COOLDOWN_LENGTH: usize = 4;
+const BUMP_LENGTH: usize = 1;
+const BOOL_LENGTH: usize = 1;
+const LIMIT_LENGTH: usize = 2;
+const HASH_LENGTH: usize = 32;
+const DAY_LENGTH: usize = 4;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const SECONDS_PER_MONTH: i64 = 30 * SECONDS_PER_DAY;
+const MAX_SUBSCRIPTION_MONTHS: u8 = 12;
+const DEFAULT_INACTIVITY_WINDOW: i64 = 365 * SECONDS_PER_DAY;
+const IMPRESSION_SYNC_WINDOW: i64 = 60;
+const REPORT_COUNTER_LENGTH: usize = 4;
+const REASON_LENGTH: usize = 1;
+const REPORT_REASON_COUNT: u8 = 5; // Spam, abuse, misinformation, illegal content, other.
+const REVISION_INDEX_LENGTH: usize = 2;
+const PAGE_LENGTH: usize = 4;
+const VEC_LENGTH_PREFIX: usize = 4; // Stores the number of items in the vector.
+const AUTHOR_INDEX_PAGE_SIZE: usize = 50; // 50 tweets per page.
+const TOPIC_INDEX_CAPACITY: usize = 10; // Keeps the 10 most recent tweets.
+const MAX_DISPLAY_NAME_LENGTH: usize = 32 * 4; // 32 chars max.
+const MAX_BIO_LENGTH: usize = 160 * 4; // 160 chars max.
+const MAX_AVATAR_URI_LENGTH: usize = 200 * 4; // 200 chars max.
+const MAX_MENTIONS: usize = 10;
+const MAX_BATCH_SIZE: usize = 5; // Keeps a batch within the transaction size limit.
+const MAX_DELETE_BATCH_SIZE: usize = 20; // Keeps a batch within the compute budget.
+const MAX_MEDIA_ATTACHMENTS: usize = 4;
+const MAX_MEDIA_URI_CHARS: usize = 200;
+const MEDIA_KIND_LENGTH: usize = 1;
+const MAX_CO_AUTHORS: usize = 3;
+const CO_AUTHOR_LENGTH: usize = PUBLIC_KEY_LENGTH + 2; // Key and basis points.
+const MAX_BPS: u64 = 10_000;
+const MAX_REVISIONS: usize = 16;
+const MAX_EDIT_LOG_ENTRIES: usize = 5;
+const MAX_POLL_OPTIONS: usize = 4;
+const MAX_POLL_OPTION_LENGTH: usize = 50 * 4; // 50 chars max.
+const OPTION_INDEX_LENGTH: usize = 1;
+const MAX_HANDLE_LENGTH: usize = 15; // 15 ASCII chars max.
+const MAX_HASHTAG_LENGTH: usize = 32; // 32 bytes max, the size limit of a seed.
+const MAX_CIPHERTEXT_LENGTH: usize = 512; // 512 bytes max.
+const MAX_NFT_NAME_LENGTH: usize = 32; // 32 bytes max, enforced by the metadata program.
+const MAX_NFT_URI_LENGTH: usize = 200; // 200 bytes max, enforced by the metadata program.
+const NFT_SYMBOL: &str = "TWEET";
+
+impl Tweet {
+    /// A published top-level tweet without interactions. Every instruction creating a tweet
+    /// starts from it so none of them can forget a field.
+    fn new(
+        author: Pubkey,
+        posted_by: Pubkey,
+        topic: String,
+        content: String,
+        timestamp: i64,
+    ) -> Self {
+        Tweet {
+            author,
+            timestamp,
+            reply_to: None,
+            content_hash: hash(content.as_bytes()).to_bytes(),
+            topic,
+            content,
+            edited_at: None,
+            like_count: 0,
+            comments: 0,
+            retweet_count: 0,
+            quote_count: 0,
+            quoted_tweet: None,
+            tips_received: 0,
+            author_index: None,
+            reply_count: 0,
+            total_tips_spl: 0,
+            thread_root: None,
+            mentions: Vec::new(),
+            is_deleted: false,
+            reports: 0,
+            hidden: false,
+            revision_count: 0,
+            published: true,
+            publish_at: None,
+            expires_at: None,
+            version: TWEET_VERSION,
+            reply_permission: REPLY_EVERYONE,
+            scheduled_reveal: None,
+            is_revealed: true,
+            weighted_like_score: 0,
+            media: Vec::new(),
+            nft_mint: None,
+            preview: String::new(),
+            gate_mint: None,
+            gate_min_amount: 0,
+            posted_by,
+            storage_kind: STORAGE_INLINE,
+            content_uri: String::new(),
+            reaction_counts: [0; REACTION_KIND_COUNT],
+            imported_likes_root: [0; 32],
+            imported_likes_count: 0,
+            views: 0,
+            last_impression_sync: 0,
+            view_count: 0,
+            space: None,
+            co_authors: Vec::new(),
+        }
+    }
+
+    fn is_expired(&self, clock: &Clock) -> bool {
+        matches!(self.expires_at, Some(expires_at) if clock.unix_timestamp >= expires_at)
+    }
+
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Author.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Reply to.
+        + STRING_LENGTH_PREFIX + MAX_TOPIC_LENGTH // Topic.
+        + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH // Content.
+        + OPTION_PREFIX_LENGTH + TIMESTAMP_LENGTH // Edited at.
+        + COUNTER_LENGTH // Like count.
+        + COUNTER_LENGTH // Comments.
+        + COUNTER_LENGTH // Retweet count.
+        + COUNTER_LENGTH // Quote count.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Quoted tweet.
+        + COUNTER_LENGTH // Tips received.
+        + OPTION_PREFIX_LENGTH + COUNTER_LENGTH // Author index.
+        + COUNTER_LENGTH // Reply count.
+        + COUNTER_LENGTH // Total SPL tips, in raw amounts across all mints.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Thread root.
+        + VEC_LENGTH_PREFIX + MAX_MENTIONS * PUBLIC_KEY_LENGTH // Mentions.
+        + BOOL_LENGTH // Is deleted.
+        + HASH_LENGTH // Content hash.
+        + REPORT_COUNTER_LENGTH // Reports.
+        + BOOL_LENGTH // Hidden.
+        + REVISION_INDEX_LENGTH // Revision count.
+        + BOOL_LENGTH // Published.
+        + OPTION_PREFIX_LENGTH + TIMESTAMP_LENGTH // Publish at.
+        + OPTION_PREFIX_LENGTH + TIMESTAMP_LENGTH // Expires at.
+        + VERSION_LENGTH // Version.
+        + REPLY_PERMISSION_LENGTH // Reply permission.
+        + OPTION_PREFIX_LENGTH + TIMESTAMP_LENGTH // Scheduled reveal.
+        + BOOL_LENGTH // Is revealed.
+        + COUNTER_LENGTH // Weighted like score, in staked lamports.
+        + VEC_LENGTH_PREFIX // Media, the attachments are sized separately.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // NFT mint.
+        + STRING_LENGTH_PREFIX // Preview, sized separately for long-form tweets.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Gate mint.
+        + COUNTER_LENGTH // Gate min amount.
+        + PUBLIC_KEY_LENGTH // Posted by.
+        + STORAGE_KIND_LENGTH // Storage kind.
+        + STRING_LENGTH_PREFIX // Content URI, sized separately for ref tweets.
+        + REACTION_KIND_COUNT * REACTION_COUNTER_LENGTH // Reaction counts.
+        + HASH_LENGTH // Imported likes root.
+        + COUNTER_LENGTH // Imported likes count.
+        + COUNTER_LENGTH // Views.
+        + TIMESTAMP_LENGTH // Last impression sync.
+        + COUNTER_LENGTH // View count.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Space.
+        + VEC_LENGTH_PREFIX + MAX_CO_AUTHORS * CO_AUTHOR_LENGTH // Co-authors.
+        + RESERVED_LENGTH; // Reserved for future fields.
+
+    /// Space of a ref tweet, which stores the URI of its content instead of the content.
+    fn ref_space(content_uri: &str) -> usize {
+        Tweet::LEN - MAX_CONTENT_LENGTH + content_uri.len()
+    }
+
+    /// Space a long-form tweet needs on top of `Tweet::LEN`, for the content beyond the
+    /// regular maximum and for its preview.
+    fn long_form_space(content: &str) -> usize {
+        content.len().saturating_sub(MAX_CONTENT_LENGTH) + preview_end(content)
+    }
+
+    /// Space the account holds for content beyond the regular maximum, from a long-form tweet
+    /// or bought through `realloc_tweet`, once its media and preview are accounted for.
+    fn extra_content_space(&self, data_len: usize) -> usize {
+        data_len.saturating_sub(Tweet::LEN + Tweet::media_space(&self.media) + self.preview.len())
+    }
+
+    /// Space taken by media attachments, on top of `Tweet::LEN`.
+    fn media_space(media: &[MediaAttachment]) -> usize {
+        media
+            .iter()
+            .map(|attachment| MEDIA_KIND_LENGTH + STRING_LENGTH_PREFIX + attachment.uri.len())
+            .sum()
+    }
+
+    /// Space missing from a tweet of an older version, as the fields added since then
+    /// outgrew its reserved space. Each version adds its own fields to the next one's.
+    fn migration_space(version: u8) -> usize {
+        match version {
+            0 => Tweet::VERSIONED_LEN,
+            1 | 2 => {
+                OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Gate mint.
+                    + COUNTER_LENGTH // Gate min amount.
+                    + Tweet::migration_space(3)
+            }
+            3 => PUBLIC_KEY_LENGTH + Tweet::migration_space(4), // Posted by.
+            4 => {
+                STORAGE_KIND_LENGTH // Storage kind.
+                    + STRING_LENGTH_PREFIX // Content URI.
+                    + Tweet::migration_space(5)
+            }
+            5 => {
+                REACTION_KIND_COUNT * REACTION_COUNTER_LENGTH // Reaction counts.
+                    + Tweet::migration_space(6)
+            }
+            6 => {
+                HASH_LENGTH // Imported likes root.
+                    + COUNTER_LENGTH // Imported likes count.
+                    + Tweet::migration_space(7)
+            }
+            7 => {
+                COUNTER_LENGTH // Views.
+                    + TIMESTAMP_LENGTH // Last impression sync.
+                    + Tweet::migration_space(8)
+            }
+            8 => COUNTER_LENGTH + Tweet::migration_space(9), // View count.
+            9 => OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH + Tweet::migration_space(10), // Space.
+            _ => VEC_LENGTH_PREFIX + MAX_CO_AUTHORS * CO_AUTHOR_LENGTH, // Co-authors.
+        }
+    }
+
+    /// Space of the version, the fields added since accounts are versioned and the
+    /// reserved space, all missing from tweets created before versioning.
+    const VERSIONED_LEN: usize = VERSION_LENGTH
+        + REPLY_PERMISSION_LENGTH // Reply permission.
+        + OPTION_PREFIX_LENGTH + TIMESTAMP_LENGTH // Scheduled reveal.
+        + BOOL_LENGTH // Is revealed.
+        + COUNTER_LENGTH // Weighted like score, in staked lamports.
+        + VEC_LENGTH_PREFIX // Media, the attachments are sized separately.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // NFT mint.
+        + STRING_LENGTH_PREFIX // Preview, sized separately for long-form tweets.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Gate mint.
+        + COUNTER_LENGTH // Gate min amount.
+        + PUBLIC_KEY_LENGTH // Posted by.
+        + STORAGE_KIND_LENGTH // Storage kind.
+        + STRING_LENGTH_PREFIX // Content URI, sized separately for ref tweets.
+        + REACTION_KIND_COUNT * REACTION_COUNTER_LENGTH // Reaction counts.
+        + HASH_LENGTH // Imported likes root.
+        + COUNTER_LENGTH // Imported likes count.
+        + COUNTER_LENGTH // Views.
+        + TIMESTAMP_LENGTH // Last impression sync.
+        + COUNTER_LENGTH // View count.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Space.
+        + VEC_LENGTH_PREFIX + MAX_CO_AUTHORS * CO_AUTHOR_LENGTH // Co-authors.
+        + RESERVED_LENGTH; // Reserved for future fields.
+}
+
+#[account]
+pub struct UserProfile {
+    pub authority: Pubkey,
+    pub display_name: String,
+    pub bio: String,
+    pub avatar_uri: String,
+    pub tweet_count: u64,
+    pub followers: u64,
+    pub following: u64,
+    pub pinned_tweet: Option<Pubkey>,
+    pub index_page: u32,
+    pub verified: bool,
+    pub likes_received: u64,
+    pub last_post_ts: i64,
+    pub handle: Option<String>,
+    pub active: bool,
+    pub subscription_price: u64,
+    pub created_at: i64,
+    // The index of the next list, it never goes down so deleted lists keep their address.
+    pub list_count: u8,
+    // Must be signed along with sponsored tweets, it goes up with each one.
+    pub nonce: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl UserProfile {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Authority.
+        + STRING_LENGTH_PREFIX + MAX_DISPLAY_NAME_LENGTH // Display name.
+        + STRING_LENGTH_PREFIX + MAX_BIO_LENGTH // Bio.
+        + STRING_LENGTH_PREFIX + MAX_AVATAR_URI_LENGTH // Avatar URI.
+        + COUNTER_LENGTH // Tweet count.
+        + COUNTER_LENGTH // Followers.
+        + COUNTER_LENGTH // Following.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Pinned tweet.
+        + PAGE_LENGTH // Index page.
+        + BOOL_LENGTH // Verified.
+        + COUNTER_LENGTH // Likes received.
+        + TIMESTAMP_LENGTH // Last post timestamp.
+        + OPTION_PREFIX_LENGTH + STRING_LENGTH_PREFIX + MAX_HANDLE_LENGTH // Handle.
+        + BOOL_LENGTH // Active.
+        + LAMPORTS_LENGTH // Subscription price.
+        + TIMESTAMP_LENGTH // Created at.
+        + LIST_INDEX_LENGTH // List count.
+        + COUNTER_LENGTH // Nonce.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+
+    /// A profile with an empty name, bio and avatar, also created by the first action of a user
+    /// who did not create one beforehand.
+    fn new(authority: Pubkey, created_at: i64, bump: u8) -> Self {
+        UserProfile {
+            authority,
+            display_name: String::new(),
+            bio: String::new(),
+            avatar_uri: String::new(),
+            tweet_count: 0,
+            followers: 0,
+            following: 0,
+            pinned_tweet: None,
+            index_page: 0,
+            verified: false,
+            likes_received: 0,
+            last_post_ts: 0,
+            handle: None,
+            active: true,
+            subscription_price: 0,
+            created_at,
+            list_count: 0,
+            nonce: 0,
+            bump,
+            version: 0,
+        }
+    }
+}
+
+#[account]
+pub struct Like {
+    pub tweet: Pubkey,
+    pub liker: Pubkey,
+    pub timestamp: i64,
+    pub staked: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Like {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Liker.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + LAMPORTS_LENGTH // Staked.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+/// Records that `liker` claimed their imported like of `tweet`. It outlives the Like, which
+/// `unlike_tweet` closes, so the claim cannot be replayed.
+#[account]
+pub struct LikeClaim {
+    pub tweet: Pubkey,
+    pub liker: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl LikeClaim {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Liker.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Comment {
+    pub author: Pubkey,
+    pub parent_tweet: Pubkey,
+    pub timestamp: i64,
+    pub content: String,
+    pub version: u8,
+}
+
+impl Comment {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Author.
+        + PUBLIC_KEY_LENGTH // Parent tweet.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH // Content.
+        + 103 // Padding, above the sizes of ref tweets which would match otherwise.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Follow {
+    pub follower: Pubkey,
+    pub followed: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Follow {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Follower.
+        + PUBLIC_KEY_LENGTH // Followed.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Retweet {
+    pub original_tweet: Pubkey,
+    pub retweeter: Pubkey,
+    pub timestamp: i64,
+    pub quote: Option<String>,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Retweet {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Original tweet.
+        + PUBLIC_KEY_LENGTH // Retweeter.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + OPTION_PREFIX_LENGTH + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH // Quote.
+        + BUMP_LENGTH // Bump.
+        + 102 // Padding, above the sizes of ref tweets which would match otherwise.
+        + VERSION_LENGTH; // Version.
+}
+
+#[event]
+pub struct TweetSent {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub topic: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TweetUpdated {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub topic: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TweetTopicChanged {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub old_topic: String,
+    pub new_topic: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TweetDeleted {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+}
+
+#[event]
+pub struct TweetFlaggedForReview {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub reports: u32,
+}
+
+#[event]
+pub struct TweetRevealed {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TweetLiked {
+    pub tweet: Pubkey,
+    pub liker: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TweetUnliked {
+    pub tweet: Pubkey,
+    pub liker: Pubkey,
+}
+
+#[event]
+pub struct TweetRetweeted {
+    pub original_tweet: Pubkey,
+    pub retweeter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RetweetUndone {
+    pub original_tweet: Pubkey,
+    pub retweeter: Pubkey,
+}
+
+#[event]
+pub struct VerificationChanged {
+    pub profile: Pubkey,
+    pub authority: Pubkey,
+    pub verified: bool,
+}
+
+#[account]
+pub struct Handle {
+    pub owner: Pubkey,
+    pub handle: String,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Handle {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Owner.
+        + STRING_LENGTH_PREFIX + MAX_HANDLE_LENGTH // Handle.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Hashtag {
+    pub tag: String,
+    pub tweet_count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Hashtag {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + STRING_LENGTH_PREFIX + MAX_HASHTAG_LENGTH // Tag.
+        + COUNTER_LENGTH // Tweet count.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct TipStats {
+    pub tweet: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub tip_count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TipStats {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Mint.
+        + COUNTER_LENGTH // Total amount.
+        + COUNTER_LENGTH // Tip count.
+        + BUMP_LENGTH // Bump.
+        + 5 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct AuthorIndex {
+    pub author: Pubkey,
+    pub page: u32,
+    pub tweets: Vec<Pubkey>,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl AuthorIndex {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Author.
+        + PAGE_LENGTH // Page.
+        + VEC_LENGTH_PREFIX + AUTHOR_INDEX_PAGE_SIZE * PUBLIC_KEY_LENGTH // Tweets.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Bookmark {
+    pub user: Pubkey,
+    pub tweet: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Bookmark {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // User.
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 2 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct View {
+    pub tweet: Pubkey,
+    pub viewer: Pubkey,
+    pub viewed_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl View {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Viewer.
+        + TIMESTAMP_LENGTH // Viewed at.
+        + BUMP_LENGTH // Bump.
+        + 3 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct TopicIndex {
+    pub topic: String,
+    pub tweet_count: u64,
+    pub recent_tweets: Vec<Pubkey>, // Oldest first.
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TopicIndex {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + STRING_LENGTH_PREFIX + MAX_TOPIC_LENGTH // Topic.
+        + COUNTER_LENGTH // Tweet count.
+        + VEC_LENGTH_PREFIX + TOPIC_INDEX_CAPACITY * PUBLIC_KEY_LENGTH // Recent tweets.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Poll {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub question: String,
+    pub options: Vec<String>,
+    pub votes: Vec<u64>,
+    pub timestamp: i64,
+    pub ends_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Poll {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Author.
+        + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH // Question.
+        + VEC_LENGTH_PREFIX // Options count.
+        + MAX_POLL_OPTIONS * (STRING_LENGTH_PREFIX + MAX_POLL_OPTION_LENGTH) // Options.
+        + VEC_LENGTH_PREFIX + MAX_POLL_OPTIONS * COUNTER_LENGTH // Votes.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + TIMESTAMP_LENGTH // Ends at.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct PollVote {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub option_index: u8,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl PollVote {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Poll.
+        + PUBLIC_KEY_LENGTH // Voter.
+        + OPTION_INDEX_LENGTH // Option index.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Block {
+    pub blocker: Pubkey,
+    pub blocked: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Block {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Blocker.
+        + PUBLIC_KEY_LENGTH // Blocked.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 4 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Delegate {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Delegate {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Owner.
+        + PUBLIC_KEY_LENGTH // Delegate.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 5 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+/// A paid subscription of a subscriber to a creator, active until `expires_at`.
+#[account]
+pub struct Subscription {
+    pub creator: Pubkey,
+    pub subscriber: Pubkey,
+    pub price: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Subscription {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Creator.
+        + PUBLIC_KEY_LENGTH // Subscriber.
+        + LAMPORTS_LENGTH // Monthly price.
+        + TIMESTAMP_LENGTH // Expiry.
+        + BUMP_LENGTH // Bump.
+        + 6 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+/// The reaction of a user to a tweet, one per user and tweet whatever its kind.
+#[account]
+pub struct Reaction {
+    pub tweet: Pubkey,
+    pub user: Pubkey,
+    pub kind: ReactionKind,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Reaction {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // User.
+        + REACTION_KIND_LENGTH // Kind.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 10 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+/// A curated group of accounts, its members each have a ListMember PDA.
+#[account]
+pub struct List {
+    pub owner: Pubkey,
+    pub index: u8,
+    pub name: String,
+    pub member_count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl List {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Owner.
+        + LIST_INDEX_LENGTH // Index.
+        + STRING_LENGTH_PREFIX + MAX_LIST_NAME_LENGTH // Name.
+        + COUNTER_LENGTH // Member count.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct ListMember {
+    pub list: Pubkey,
+    pub member: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ListMember {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // List.
+        + PUBLIC_KEY_LENGTH // Member.
+        + TIMESTAMP_LENGTH // Added at.
+        + BUMP_LENGTH // Bump.
+        + 6 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct TweetList {
+    pub owner: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub item_count: u64,
+    pub version: u8,
+}
+
+impl TweetList {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Owner.
+        + STRING_LENGTH_PREFIX + MAX_TWEET_LIST_NAME_LENGTH // Name.
+        + STRING_LENGTH_PREFIX + MAX_TWEET_LIST_DESCRIPTION_LENGTH // Description.
+        + COUNTER_LENGTH // Item count.
+        + VERSION_LENGTH; // Version.
+}
+
+/// Clients list the tweets of a list with a memcmp filter on `list`, right after the
+/// discriminator.
+#[account]
+pub struct ListItem {
+    pub list: Pubkey,
+    pub tweet: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ListItem {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // List.
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + TIMESTAMP_LENGTH // Added at.
+        + BUMP_LENGTH // Bump.
+        + 7 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Space {
+    pub owner: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub member_count: u64,
+    pub tweet_count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Space {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Owner.
+        + STRING_LENGTH_PREFIX + MAX_SPACE_NAME_LENGTH // Name.
+        + STRING_LENGTH_PREFIX + MAX_SPACE_DESCRIPTION_LENGTH // Description.
+        + COUNTER_LENGTH // Member count.
+        + COUNTER_LENGTH // Tweet count.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct SpaceMember {
+    pub space: Pubkey,
+    pub user: Pubkey,
+    pub joined_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SpaceMember {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Space.
+        + PUBLIC_KEY_LENGTH // User.
+        + TIMESTAMP_LENGTH // Joined at.
+        + BUMP_LENGTH // Bump.
+        + 9 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Mention {
+    pub mentioned: Pubkey,
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Mention {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Mentioned.
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Author.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub paused: bool,
+    pub max_content_len: u16,
+    pub max_topic_len: u16,
+    pub duplicate_window_secs: i64,
+    pub report_threshold: u32,
+    pub post_fee_lamports: u64,
+    pub tweet_cooldown_secs: u32,
+    pub min_post_interval: i64,
+    pub gate_mint: Option<Pubkey>,
+    pub inactivity_window: i64,
+    // The off-chain indexer allowed to push view counts.
+    pub oracle: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Config {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Admin.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Pending admin.
+        + BOOL_LENGTH // Paused.
+        + LIMIT_LENGTH // Max content length.
+        + LIMIT_LENGTH // Max topic length.
+        + TIMESTAMP_LENGTH // Duplicate window.
+        + REPORT_COUNTER_LENGTH // Report threshold.
+        + LAMPORTS_LENGTH // Post fee.
+        + COOLDOWN_LENGTH // Tweet cooldown.
+        + TIMESTAMP_LENGTH // Min post interval.
+        + OPTION_PREFIX_LENGTH + PUBLIC_KEY_LENGTH // Gate mint.
+        + TIMESTAMP_LENGTH // Inactivity window.
+        + PUBLIC_KEY_LENGTH // Oracle.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+/// Collects the posting fees. Its lamports are the balance, it only stores its bump.
+#[account]
+pub struct Treasury {
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Treasury {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+/// Program-wide counters, bumped by the instructions that were given the account.
+#[account]
+pub struct GlobalStats {
+    pub total_tweets: u64,
+    pub total_likes: u64,
+    pub total_users: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl GlobalStats {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + COUNTER_LENGTH // Total tweets.
+        + COUNTER_LENGTH // Total likes.
+        + COUNTER_LENGTH // Total users.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+/// The counters of a single UTC day, the number of days since the Unix epoch, so the
+/// activity can be charted without an indexer.
+#[account]
+pub struct DailyStats {
+    pub day: u32,
+    pub tweets: u64,
+    pub likes: u64,
+    pub users: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl DailyStats {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + DAY_LENGTH // Day.
+        + COUNTER_LENGTH // Tweets.
+        + COUNTER_LENGTH // Likes.
+        + COUNTER_LENGTH // Users.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+/// The counter of the statistics bumped by an instruction.
+#[derive(Clone, Copy)]
+enum Stat {
+    Tweet,
+    Like,
+    User,
+}
+
+#[account]
+pub struct RecentPost {
+    pub author: Pubkey,
+    pub content_hash: [u8; 32],
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl RecentPost {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Author.
+        + HASH_LENGTH // Content hash.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 10 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Report {
+    pub reporter: Pubkey,
+    pub tweet: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Report {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Reporter.
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + REASON_LENGTH // Reason.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + BUMP_LENGTH // Bump.
+        + 11 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct TweetRevision {
+    pub tweet: Pubkey,
+    pub author: Pubkey,
+    pub index: u16,
+    pub topic: String,
+    pub content: String,
+    pub edited_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TweetRevision {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + PUBLIC_KEY_LENGTH // Author.
+        + REVISION_INDEX_LENGTH // Index.
+        + STRING_LENGTH_PREFIX + MAX_TOPIC_LENGTH // Topic.
+        + STRING_LENGTH_PREFIX + MAX_CONTENT_LENGTH // Content.
+        + TIMESTAMP_LENGTH // Edited at.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EditLogEntry {
+    pub previous_content_hash: [u8; 32],
+    pub edited_at: i64,
+}
+
+/// Ring buffer of the last edits of a tweet. Once full, each edit overwrites the oldest
+/// entry, which sits at `count % MAX_EDIT_LOG_ENTRIES`.
+#[account]
+pub struct EditLog {
+    pub tweet: Pubkey,
+    pub entries: Vec<EditLogEntry>,
+    pub count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl EditLog {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Tweet.
+        + VEC_LENGTH_PREFIX + MAX_EDIT_LOG_ENTRIES * (HASH_LENGTH + TIMESTAMP_LENGTH) // Entries.
+        + COUNTER_LENGTH // Count.
+        + BUMP_LENGTH // Bump.
+        + VERSION_LENGTH; // Version.
+
+    fn append(&mut self, entry: EditLogEntry) -> Result<()> {
+        if self.entries.len() < MAX_EDIT_LOG_ENTRIES {
+            self.entries.push(entry);
+        } else {
+            self.entries[(self.count % MAX_EDIT_LOG_ENTRIES as u64) as usize] = entry;
+        }
+
+        self.count.increment()
+    }
+}
+
+#[account]
+pub struct Mute {
+    pub muter: Pubkey,
+    pub muted: Pubkey,
+    pub timestamp: i64,
+    pub version: u8,
+}
+
+impl Mute {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Muter.
+        + PUBLIC_KEY_LENGTH // Muted.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Message {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub conversation: Pubkey,
+    pub timestamp: i64,
+    pub ciphertext: Vec<u8>,
+    pub version: u8,
+}
+
+impl Message {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // Sender.
+        + PUBLIC_KEY_LENGTH // Recipient.
+        + PUBLIC_KEY_LENGTH // Conversation.
+        + TIMESTAMP_LENGTH // Timestamp.
+        + VEC_LENGTH_PREFIX + MAX_CIPHERTEXT_LENGTH // Ciphertext.
+        + VERSION_LENGTH; // Version.
+}
+
+#[account]
+pub struct Conversation {
+    pub user_a: Pubkey,
+    pub user_b: Pubkey,
+    pub message_count: u64,
+    pub last_message_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Conversation {
+    const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // User A, the smaller key.
+        + PUBLIC_KEY_LENGTH // User B, the larger key.
+        + COUNTER_LENGTH // Message count.
+        + TIMESTAMP_LENGTH // Last message at.
+        + BUMP_LENGTH // Bump.
+        + 7 // Padding, no other account has the same size.
+        + VERSION_LENGTH; // Version.
 }
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("The provided topic should be 50 characters long maximum.")]
+    #[msg("The provided topic is longer than the configured maximum.")]
     TopicTooLong,
-    #[msg("The provided content should be 280 characters long maximum.")]
+    #[msg("The provided content is longer than the configured maximum.")]
     ContentTooLong,
+    #[msg("The provided long-form content should be 4000 characters long maximum.")]
+    LongContentTooLong,
+    #[msg("The provided display name should be 32 characters long maximum.")]
+    DisplayNameTooLong,
+    #[msg("The provided bio should be 160 characters long maximum.")]
+    BioTooLong,
+    #[msg("The provided avatar URI should be 200 characters long maximum.")]
+    AvatarUriTooLong,
+    #[msg("A numerical value overflowed.")]
+    NumericalOverflow,
+    #[msg("A counter cannot go below zero.")]
+    CounterUnderflow,
+    #[msg("The tweet cannot be deleted while it still has comments.")]
+    TweetHasComments,
+    #[msg("You cannot follow yourself.")]
+    CannotFollowSelf,
+    #[msg("The provided handle should be 3 characters long minimum.")]
+    HandleTooShort,
+    #[msg("The provided handle should be 15 characters long maximum.")]
+    HandleTooLong,
+    #[msg("The provided handle can only contain lowercase letters, digits and underscores.")]
+    InvalidHandleCharacter,
+    #[msg("The tip amount should be greater than zero.")]
+    ZeroTip,
+    #[msg("You cannot tip your own tweet.")]
+    CannotTipOwnTweet,
+    #[msg("You can only pin your own tweets.")]
+    CannotPinOthersTweet,
+    #[msg("The provided account is not owned by the program.")]
+    AccountNotOwnedByProgram,
+    #[msg("A hashtag account is missing or does not match the hashtags of the content.")]
+    HashtagAccountMismatch,
+    #[msg("The token account does not hold the expected mint.")]
+    TokenMintMismatch,
+    #[msg("The token account is not owned by the expected wallet.")]
+    TokenOwnerMismatch,
+    #[msg("The author index page is missing or does not match the expected page.")]
+    IndexPageMismatch,
+    #[msg("Tweets derived from their author's address cannot be transferred.")]
+    CannotTransferPdaTweet,
+    #[msg("A poll should have between 2 and 4 options.")]
+    InvalidPollOptionCount,
+    #[msg("The provided poll option should be 50 characters long maximum.")]
+    PollOptionTooLong,
+    #[msg("The poll duration should be greater than zero.")]
+    InvalidPollDuration,
+    #[msg("This poll is closed.")]
+    PollClosed,
+    #[msg("The provided option does not exist on this poll.")]
+    InvalidOption,
+    #[msg("You cannot block yourself.")]
+    CannotBlockSelf,
+    #[msg("The author of this tweet has blocked you.")]
+    BlockedByAuthor,
+    #[msg("A tweet can mention 10 users maximum.")]
+    TooManyMentions,
+    #[msg("The same user cannot be mentioned twice.")]
+    DuplicateMention,
+    #[msg("A mention account is missing or does not match the mentioned users.")]
+    MentionAccountMismatch,
+    #[msg("Only the admin can perform this action.")]
+    Unauthorized,
+    #[msg("The admin cannot be the default public key.")]
+    InvalidAdmin,
+    #[msg("The program is paused.")]
+    ProgramPaused,
+    #[msg("The provided limits do not fit in a tweet account.")]
+    InvalidConfig,
+    #[msg("This tweet has replies, soft delete it instead.")]
+    TweetHasReplies,
+    #[msg("You already posted this content recently.")]
+    DuplicateContent,
+    #[msg("This tweet was hidden by a moderator.")]
+    TweetHidden,
+    #[msg("A batch can contain 5 tweets maximum.")]
+    BatchTooLarge,
+    #[msg("A batch needs exactly one tweet account per tweet.")]
+    BatchAccountMismatch,
+    #[msg("Each tweet account of a batch should be a new account signing the transaction.")]
+    InvalidBatchAccount,
+    #[msg("This tweet cannot be edited more than 16 times.")]
+    TooManyEdits,
+    #[msg("The publication time should be in the future.")]
+    InvalidPublishTime,
+    #[msg("This tweet is not published yet.")]
+    TweetNotPublished,
+    #[msg("This tweet is already published.")]
+    TweetAlreadyPublished,
+    #[msg("This tweet cannot be published yet.")]
+    PublishTimeNotReached,
+    #[msg("The expiration time should be in the future.")]
+    InvalidExpiration,
+    #[msg("This tweet has expired.")]
+    TweetExpired,
+    #[msg("This tweet has not expired yet.")]
+    TweetNotExpired,
+    #[msg("A tweet account can only grow.")]
+    TweetCannotShrink,
+    #[msg("You cannot send a message to yourself.")]
+    CannotMessageSelf,
+    #[msg("The provided ciphertext should be 512 bytes long maximum.")]
+    CiphertextTooLong,
+    #[msg("Unknown report reason.")]
+    InvalidReportReason,
+    #[msg("This account must be migrated first.")]
+    AccountVersionTooOld,
+    #[msg("This account is already up to date.")]
+    AccountAlreadyMigrated,
+    #[msg("Unknown reply permission.")]
+    InvalidReplyPermission,
+    #[msg("The author restricted who can reply to this tweet.")]
+    RepliesRestricted,
+    #[msg("The treasury cannot go below its rent-exempt minimum.")]
+    InsufficientTreasuryFunds,
+    #[msg("The reveal time should be in the future.")]
+    InvalidRevealTime,
+    #[msg("This tweet cannot be revealed yet.")]
+    NotYetRevealable,
+    #[msg("This tweet is already revealed.")]
+    TweetAlreadyRevealed,
+    #[msg("The author's RecentPost account is required while a cooldown is active.")]
+    RecentPostRequired,
+    #[msg("You need to wait before tweeting again.")]
+    CooldownActive,
+    #[msg("The author's profile is required.")]
+    ProfileRequired,
+    #[msg("You are posting too fast, please wait before posting again.")]
+    PostingTooFast,
+    #[msg("The minimum post interval cannot be negative.")]
+    InvalidPostInterval,
+    #[msg("The staked amount should be greater than zero.")]
+    InvalidStakeAmount,
+    #[msg("A tweet can have 4 media attachments maximum.")]
+    TooManyAttachments,
+    #[msg("The provided media URI should be 200 characters long maximum.")]
+    MediaUriTooLong,
+    #[msg("This tweet has already been minted as an NFT.")]
+    AlreadyMinted,
+    #[msg("Only holders of the gate token can interact with this tweet.")]
+    NotTokenHolder,
+    #[msg("The signer is not a delegate of this author.")]
+    NotADelegate,
+    #[msg("You cannot make yourself your own delegate.")]
+    CannotDelegateToSelf,
+    #[msg("The provided NFT URI should be 200 bytes long maximum.")]
+    NftUriTooLong,
+    #[msg("Only holders of the gate mint can send gated tweets.")]
+    GateNotSatisfied,
+    #[msg("This tweet has been deleted.")]
+    TweetSoftDeleted,
+    #[msg("Only soft-deleted tweets can be reclaimed, delete this tweet instead.")]
+    TweetNotSoftDeleted,
+    #[msg("This tweet can only be reclaimed once its replies are deleted.")]
+    TombstoneHasReplies,
+    #[msg("A profile can only be closed once all its tweets are deleted.")]
+    ProfileNotEmpty,
+    #[msg("This profile has been deactivated.")]
+    ProfileDeactivated,
+    #[msg("A subscription lasts between 1 and 12 months.")]
+    InvalidSubscriptionMonths,
+    #[msg("This creator does not offer subscriptions.")]
+    SubscriptionsClosed,
+    #[msg("You cannot subscribe to yourself.")]
+    CannotSubscribeToSelf,
+    #[msg("The provided content URI should be 128 bytes long maximum.")]
+    ContentUriTooLong,
+    #[msg("The content hash of a ref tweet cannot be empty.")]
+    InvalidContentHash,
+    #[msg("Ref tweets store their content off chain and cannot be edited.")]
+    RefTweetNotEditable,
+    #[msg("A delete batch can contain 20 tweets maximum.")]
+    DeleteBatchTooLarge,
+    #[msg("Only the author of a tweet can delete it.")]
+    NotAuthor,
+    #[msg("A tweet cannot be empty or contain only whitespace.")]
+    ContentEmpty,
+    #[msg("This profile was active too recently to be reclaimed.")]
+    ProfileStillActive,
+    #[msg("The inactivity window should be positive.")]
+    InvalidInactivityWindow,
+    #[msg("The provided list name should be 32 characters long maximum.")]
+    ListNameTooLong,
+    #[msg("A list can contain 100 members maximum.")]
+    ListFull,
+    #[msg("Only the owner of a list can change it.")]
+    NotListOwner,
+    #[msg("A list can only be deleted once all its members are removed.")]
+    ListNotEmpty,
+    #[msg("The provided merkle proof is invalid.")]
+    InvalidProof,
+    #[msg("Only the oracle can record impressions.")]
+    NotOracle,
+    #[msg("The impressions of this tweet were synced less than a minute ago.")]
+    SyncTooSoon,
+    #[msg("The provided list name should be 50 characters long maximum.")]
+    TweetListNameTooLong,
+    #[msg("The provided list description should be 200 characters long maximum.")]
+    TweetListDescriptionTooLong,
+    #[msg("The provided space name should be 50 characters long maximum.")]
+    SpaceNameTooLong,
+    #[msg("The provided space description should be 200 characters long maximum.")]
+    SpaceDescriptionTooLong,
+    #[msg("Only the owner of a space can change it.")]
+    NotSpaceOwner,
+    #[msg("The space the tweet is posted into is required.")]
+    SpaceRequired,
+    #[msg("Only members of a space can post into it.")]
+    NotSpaceMember,
+    #[msg("The nonce does not match the one of the author's profile.")]
+    InvalidNonce,
+    #[msg("The Ed25519 signature of the author must be verified right before this instruction.")]
+    MissingSignature,
+    #[msg("The signature does not match the author or the content of this tweet.")]
+    InvalidSignature,
+    #[msg("A tweet can have at most 3 co-authors.")]
+    TooManyCoAuthors,
+    #[msg("The shares of the co-authors cannot exceed 10000 basis points.")]
+    CoAuthorSharesTooHigh,
+    #[msg("The co-authors must be passed in the order the tweet declares them.")]
+    CoAuthorMismatch,
+    #[msg("The same co-author cannot be listed twice.")]
+    DuplicateCoAuthor,
+    #[msg("The author cannot be their own co-author.")]
+    AuthorIsCoAuthor,
+    #[msg("Every co-author must get a share above zero basis points.")]
+    ZeroCoAuthorShare,
+    #[msg("Deleting a reply requires its parent tweet.")]
+    ParentTweetRequired,
+    #[msg("A counter cannot go above its maximum.")]
+    CountOverflow,
+    #[msg("The account is not the wallet that posted the tweet.")]
+    PostedByMismatch,
+    #[msg("Whoever posted the tweet pays for it to grow and must sign.")]
+    PostedByMustSign,
 }